@@ -0,0 +1,15 @@
+--
+-- Add the version components of java_version to JVM
+--
+-- The columns are populated by the next fetch, major is backfilled to allow exporting by major version right away
+--
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS build INTEGER;
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS major INTEGER;
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS minor INTEGER;
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS openjdk_version TEXT;
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS patch INTEGER;
+
+UPDATE JVM SET major = substring(java_version from '^(?:1\.)?([0-9]+)')::INTEGER WHERE major IS NULL;
+
+DROP INDEX IF EXISTS JVM_IDX_VENDOR_MAJOR;
+CREATE INDEX JVM_IDX_VENDOR_MAJOR ON JVM (vendor, major, os, architecture);
@@ -0,0 +1,16 @@
+--
+-- Create Table HTTP_STATS with the latency and error statistics per host recorded by fetch
+--
+CREATE TABLE IF NOT EXISTS HTTP_STATS (
+    host TEXT NOT NULL,
+    requests BIGINT NOT NULL,
+    errors BIGINT NOT NULL,
+    avg_ms BIGINT NOT NULL,
+    p95_ms BIGINT NOT NULL,
+    max_ms BIGINT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS HTTP_STATS_IDX_HOST ON HTTP_STATS (host);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON HTTP_STATS TO roast;
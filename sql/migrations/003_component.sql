@@ -0,0 +1,6 @@
+--
+-- Add the component of an artifact to JVM e.g. jvm, javafx, jmc
+--
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS component TEXT NOT NULL DEFAULT 'jvm';
+
+CREATE INDEX IF NOT EXISTS JVM_IDX_COMPONENT ON JVM (component);
@@ -0,0 +1,22 @@
+--
+-- Create Table BUILD_TOOL with the Gradle and Maven distributions
+--
+CREATE TABLE IF NOT EXISTS BUILD_TOOL (
+    "checksum" TEXT,
+    checksum_url TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    file_type TEXT NOT NULL,
+    "filename" TEXT NOT NULL,
+    modified_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    release_type TEXT NOT NULL,
+    "size" INTEGER,
+    tool TEXT NOT NULL,
+    "url" TEXT NOT NULL,
+    "version" TEXT NOT NULL,
+    /* should match the Hash/PartialEq implementation of BuildToolData (src/build_tool/mod.rs) */
+    PRIMARY KEY(url)
+);
+
+CREATE INDEX IF NOT EXISTS BUILD_TOOL_IDX_TOOL ON BUILD_TOOL (tool);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON BUILD_TOOL TO roast;
@@ -0,0 +1,6 @@
+--
+-- Add the platform requirements of an artifact to JVM e.g. { "libc": "musl" }, { "libc": "glibc", "min_glibc": "2.17" }
+--
+-- The column is populated by the vendor rules on the next fetch
+--
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS platform_requirements JSONB;
@@ -0,0 +1,12 @@
+--
+-- Create Table NOT_FOUND with the URLs which returned 404 during fetch
+--
+CREATE TABLE IF NOT EXISTS NOT_FOUND (
+    "url" TEXT NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 1,
+    first_seen_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(url)
+);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON NOT_FOUND TO roast;
@@ -0,0 +1,32 @@
+--
+-- Create Function RFC3339_UTC formatting timestamps as UTC RFC3339 e.g. 2025-01-31T12:00:00Z
+--
+CREATE OR REPLACE FUNCTION RFC3339_UTC(ts TIMESTAMPTZ) RETURNS TEXT AS $$
+    SELECT to_char(ts AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')
+$$ LANGUAGE SQL STABLE;
+
+--
+-- Store new timestamps as UTC RFC3339
+--
+ALTER TABLE JVM ALTER COLUMN created_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE JVM ALTER COLUMN modified_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE BUILD_TOOL ALTER COLUMN created_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE BUILD_TOOL ALTER COLUMN modified_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE HTTP_STATS ALTER COLUMN created_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE NOT_FOUND ALTER COLUMN first_seen_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE NOT_FOUND ALTER COLUMN last_seen_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+
+--
+-- Convert existing timestamps, which were stored with the offset of the session time zone
+--
+UPDATE JVM SET
+    created_at = RFC3339_UTC(created_at::TIMESTAMPTZ),
+    modified_at = RFC3339_UTC(modified_at::TIMESTAMPTZ);
+UPDATE BUILD_TOOL SET
+    created_at = RFC3339_UTC(created_at::TIMESTAMPTZ),
+    modified_at = RFC3339_UTC(modified_at::TIMESTAMPTZ);
+UPDATE HTTP_STATS SET
+    created_at = RFC3339_UTC(created_at::TIMESTAMPTZ);
+UPDATE NOT_FOUND SET
+    first_seen_at = RFC3339_UTC(first_seen_at::TIMESTAMPTZ),
+    last_seen_at = RFC3339_UTC(last_seen_at::TIMESTAMPTZ);
@@ -0,0 +1,8 @@
+--
+-- Store the features of JVM as JSON array instead of comma-joined text e.g. ["lite", "musl"]
+--
+ALTER TABLE JVM ALTER COLUMN features TYPE JSONB USING
+    CASE
+        WHEN features IS NULL OR features = '' THEN NULL
+        ELSE to_jsonb(string_to_array(features, ','))
+    END;
@@ -0,0 +1,12 @@
+--
+-- Create Table ASSET with the checksums of GitHub assets at the time the asset was last updated
+--
+CREATE TABLE IF NOT EXISTS ASSET (
+    "url" TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    modified_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    PRIMARY KEY(url)
+);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON ASSET TO roast;
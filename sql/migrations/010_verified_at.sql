@@ -0,0 +1,7 @@
+--
+-- Add column verified_at to JVM, the time fetch last found the record at its vendor whether it changed or not
+--
+ALTER TABLE JVM ADD COLUMN IF NOT EXISTS verified_at TEXT;
+UPDATE JVM SET verified_at = modified_at WHERE verified_at IS NULL;
+ALTER TABLE JVM ALTER COLUMN verified_at SET DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP);
+ALTER TABLE JVM ALTER COLUMN verified_at SET NOT NULL;
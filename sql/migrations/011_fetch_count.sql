@@ -0,0 +1,11 @@
+--
+-- Create Table FETCH_COUNT with the number of records every vendor returned on its latest fetch
+--
+CREATE TABLE IF NOT EXISTS FETCH_COUNT (
+    vendor TEXT NOT NULL,
+    records BIGINT NOT NULL,
+    fetched_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    PRIMARY KEY(vendor)
+);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON FETCH_COUNT TO roast;
@@ -1,24 +1,39 @@
+--
+-- Create Function RFC3339_UTC formatting timestamps as UTC RFC3339 e.g. 2025-01-31T12:00:00Z
+--
+CREATE OR REPLACE FUNCTION RFC3339_UTC(ts TIMESTAMPTZ) RETURNS TEXT AS $$
+    SELECT to_char(ts AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')
+$$ LANGUAGE SQL STABLE;
+
 --
 -- Create Table JVM
 --
 DROP TABLE IF EXISTS JVM;
 CREATE TABLE JVM (
     architecture TEXT NOT NULL,
+    build INTEGER,
     "checksum" TEXT,
     checksum_url TEXT,
-    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-    features TEXT,
+    component TEXT NOT NULL DEFAULT 'jvm',
+    created_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    features JSONB,
     file_type TEXT NOT NULL,
     "filename" TEXT,
     image_type TEXT NOT NULL,
     java_version TEXT,
     jvm_impl TEXT,
-    modified_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    major INTEGER,
+    minor INTEGER,
+    modified_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    openjdk_version TEXT,
     os TEXT NOT NULL,
+    patch INTEGER,
+    platform_requirements JSONB,
     release_type TEXT NOT NULL,
     "size" INTEGER,
     "url" TEXT NOT NULL,
     vendor TEXT NOT NULL,
+    verified_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
     "version" TEXT NOT NULL,
     /* should match the Hash/PartialEq implementation of JvmData (src/jvm/mod.rs) */
     PRIMARY KEY(url)
@@ -30,16 +45,102 @@ CREATE TABLE JVM (
 DROP INDEX IF EXISTS JVM_IDX_ARCHITECTURE;
 CREATE INDEX JVM_IDX_ARCHITECTURE ON JVM (architecture);
 
+DROP INDEX IF EXISTS JVM_IDX_COMPONENT;
+CREATE INDEX JVM_IDX_COMPONENT ON JVM (component);
+
 DROP INDEX IF EXISTS JVM_IDX_OS;
 CREATE INDEX JVM_IDX_OS ON JVM (os);
 
 DROP INDEX IF EXISTS JVM_IDX_VENDOR;
 CREATE INDEX JVM_IDX_VENDOR ON JVM (vendor);
 
+DROP INDEX IF EXISTS JVM_IDX_VENDOR_MAJOR;
+CREATE INDEX JVM_IDX_VENDOR_MAJOR ON JVM (vendor, major, os, architecture);
+
 DROP INDEX IF EXISTS JVM_IDX_VERSION;
 CREATE INDEX JVM_IDX_VERSION ON JVM ("version");
 
+--
+-- Create Table BUILD_TOOL
+--
+DROP TABLE IF EXISTS BUILD_TOOL;
+CREATE TABLE BUILD_TOOL (
+    "checksum" TEXT,
+    checksum_url TEXT,
+    created_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    file_type TEXT NOT NULL,
+    "filename" TEXT NOT NULL,
+    modified_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    release_type TEXT NOT NULL,
+    "size" INTEGER,
+    tool TEXT NOT NULL,
+    "url" TEXT NOT NULL,
+    "version" TEXT NOT NULL,
+    /* should match the Hash/PartialEq implementation of BuildToolData (src/build_tool/mod.rs) */
+    PRIMARY KEY(url)
+);
+
+DROP INDEX IF EXISTS BUILD_TOOL_IDX_TOOL;
+CREATE INDEX BUILD_TOOL_IDX_TOOL ON BUILD_TOOL (tool);
+
+--
+-- Create Table HTTP_STATS
+--
+DROP TABLE IF EXISTS HTTP_STATS;
+CREATE TABLE HTTP_STATS (
+    host TEXT NOT NULL,
+    requests BIGINT NOT NULL,
+    errors BIGINT NOT NULL,
+    avg_ms BIGINT NOT NULL,
+    p95_ms BIGINT NOT NULL,
+    max_ms BIGINT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP)
+);
+
+DROP INDEX IF EXISTS HTTP_STATS_IDX_HOST;
+CREATE INDEX HTTP_STATS_IDX_HOST ON HTTP_STATS (host);
+
+--
+-- Create Table NOT_FOUND with the URLs which returned 404 during fetch
+--
+DROP TABLE IF EXISTS NOT_FOUND;
+CREATE TABLE NOT_FOUND (
+    "url" TEXT NOT NULL,
+    failures INTEGER NOT NULL DEFAULT 1,
+    first_seen_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    last_seen_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    PRIMARY KEY(url)
+);
+
+--
+-- Create Table ASSET with the checksums of GitHub assets at the time the asset was last updated
+--
+DROP TABLE IF EXISTS ASSET;
+CREATE TABLE ASSET (
+    "url" TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    checksum TEXT NOT NULL,
+    modified_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    PRIMARY KEY(url)
+);
+
+--
+-- Create Table FETCH_COUNT with the number of records every vendor returned on its latest fetch
+--
+DROP TABLE IF EXISTS FETCH_COUNT;
+CREATE TABLE FETCH_COUNT (
+    vendor TEXT NOT NULL,
+    records BIGINT NOT NULL,
+    fetched_at TEXT NOT NULL DEFAULT RFC3339_UTC(CURRENT_TIMESTAMP),
+    PRIMARY KEY(vendor)
+);
+
 --
 -- Allow read/write for user roast
 --
 GRANT SELECT, INSERT, UPDATE, DELETE ON JVM TO roast;
+GRANT SELECT, INSERT, UPDATE, DELETE ON HTTP_STATS TO roast;
+GRANT SELECT, INSERT, UPDATE, DELETE ON BUILD_TOOL TO roast;
+GRANT SELECT, INSERT, UPDATE, DELETE ON NOT_FOUND TO roast;
+GRANT SELECT, INSERT, UPDATE, DELETE ON ASSET TO roast;
+GRANT SELECT, INSERT, UPDATE, DELETE ON FETCH_COUNT TO roast;
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use eyre::Result;
+use log::{debug, warn};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+
+use crate::http::HTTP;
+
+use super::{BuildTool, BuildToolData, fetch_checksum};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Gradle {}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GradleVersion {
+    version: String,
+    download_url: String,
+    checksum_url: String,
+    #[serde(default)]
+    snapshot: bool,
+    #[serde(default)]
+    nightly: bool,
+    #[serde(default)]
+    release_nightly: bool,
+    #[serde(default)]
+    broken: bool,
+    #[serde(default)]
+    rc_for: String,
+    #[serde(default)]
+    milestone_for: String,
+}
+
+impl BuildTool for Gradle {
+    fn get_name(&self) -> String {
+        "gradle".to_string()
+    }
+
+    fn fetch_data(&self, data: &mut HashSet<BuildToolData>) -> Result<()> {
+        debug!("[gradle] fetching versions");
+        let versions = HTTP.get_json::<Vec<GradleVersion>, _>("https://services.gradle.org/versions/all")?;
+        let distributions = versions
+            .into_par_iter()
+            .filter(include)
+            .map(map_version)
+            .collect::<Vec<BuildToolData>>();
+        data.extend(distributions);
+        Ok(())
+    }
+}
+
+fn include(version: &GradleVersion) -> bool {
+    !version.snapshot && !version.nightly && !version.release_nightly && !version.broken
+}
+
+fn map_version(version: GradleVersion) -> BuildToolData {
+    let checksum = fetch_checksum(&[("sha256", version.checksum_url.clone())]);
+    if checksum.is_none() {
+        warn!("[gradle] unable to find SHA256 for {}", version.version);
+    }
+    BuildToolData {
+        checksum: checksum.map(|(checksum, _)| checksum),
+        checksum_url: Some(version.checksum_url),
+        file_type: "zip".to_string(),
+        filename: version.download_url.rsplit('/').next().unwrap_or_default().to_string(),
+        release_type: match version.rc_for.is_empty() && version.milestone_for.is_empty() {
+            true => "ga".to_string(),
+            false => "ea".to_string(),
+        },
+        tool: "gradle".to_string(),
+        url: version.download_url,
+        version: version.version,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_include() {
+        let versions: Vec<GradleVersion> = serde_json::from_str(indoc! {r#"
+        [
+          {
+            "version": "8.13-20250101000000+0000",
+            "downloadUrl": "https://services.gradle.org/distributions-snapshots/gradle-8.13-20250101000000+0000-bin.zip",
+            "checksumUrl": "https://services.gradle.org/distributions-snapshots/gradle-8.13-20250101000000+0000-bin.zip.sha256",
+            "snapshot": true,
+            "nightly": true
+          },
+          {
+            "version": "8.12-rc-1",
+            "downloadUrl": "https://services.gradle.org/distributions/gradle-8.12-rc-1-bin.zip",
+            "checksumUrl": "https://services.gradle.org/distributions/gradle-8.12-rc-1-bin.zip.sha256",
+            "rcFor": "8.12"
+          },
+          {
+            "version": "8.12",
+            "downloadUrl": "https://services.gradle.org/distributions/gradle-8.12-bin.zip",
+            "checksumUrl": "https://services.gradle.org/distributions/gradle-8.12-bin.zip.sha256",
+            "rcFor": "",
+            "milestoneFor": ""
+          }
+        ]
+        "#})
+        .unwrap();
+
+        let included = versions.into_iter().filter(include).collect::<Vec<GradleVersion>>();
+
+        assert_eq!(
+            included.iter().map(|v| v.version.as_str()).collect::<Vec<&str>>(),
+            vec!["8.12-rc-1", "8.12"]
+        );
+        assert_eq!(included[0].rc_for, "8.12");
+    }
+}
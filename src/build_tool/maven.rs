@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use eyre::Result;
+use log::debug;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use xx::regex;
+
+use crate::http::HTTP;
+
+use super::{BuildTool, BuildToolData, fetch_checksum};
+
+#[derive(Clone, Copy, Debug)]
+pub struct Maven {}
+
+const BASE_URL: &str = "https://repo1.maven.org/maven2/org/apache/maven/apache-maven";
+
+impl BuildTool for Maven {
+    fn get_name(&self) -> String {
+        "maven".to_string()
+    }
+
+    fn fetch_data(&self, data: &mut HashSet<BuildToolData>) -> Result<()> {
+        debug!("[maven] fetching versions");
+        let metadata = HTTP.get_text(format!("{}/maven-metadata.xml", BASE_URL))?;
+        let distributions = versions_from_metadata(&metadata)
+            .into_iter()
+            .flat_map(|version| ["tar.gz", "zip"].map(|ext| (version.clone(), ext)))
+            .collect::<Vec<(String, &str)>>()
+            .into_par_iter()
+            .filter_map(|(version, ext)| map_version(&version, ext))
+            .collect::<Vec<BuildToolData>>();
+        data.extend(distributions);
+        Ok(())
+    }
+}
+
+fn versions_from_metadata(metadata: &str) -> Vec<String> {
+    regex!(r"<version>([^<]+)</version>")
+        .captures_iter(metadata)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// Returns the distribution of a version, or `None` if it is not published e.g. the zip of very old releases
+fn map_version(version: &str, ext: &str) -> Option<BuildToolData> {
+    let filename = format!("apache-maven-{}-bin.{}", version, ext);
+    let url = format!("{}/{}/{}", BASE_URL, version, filename);
+    // older releases only publish a SHA1 checksum, the checksum doubles as existence check
+    let Some((checksum, checksum_url)) =
+        fetch_checksum(&[("sha512", format!("{}.sha512", url)), ("sha1", format!("{}.sha1", url))])
+    else {
+        debug!("[maven] no distribution found for {}", filename);
+        return None;
+    };
+    Some(BuildToolData {
+        checksum: Some(checksum),
+        checksum_url: Some(checksum_url),
+        file_type: ext.to_string(),
+        filename,
+        release_type: normalize_release_type(version),
+        tool: "maven".to_string(),
+        url,
+        version: version.to_string(),
+        ..Default::default()
+    })
+}
+
+fn normalize_release_type(version: &str) -> String {
+    match regex!(r"(?i)-(alpha|beta|rc)").is_match(version) {
+        true => "ea".to_string(),
+        false => "ga".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_from_metadata() {
+        let metadata = "<versions><version>3.9.9</version><version>4.0.0-rc-2</version></versions>";
+        assert_eq!(versions_from_metadata(metadata), vec!["3.9.9", "4.0.0-rc-2"]);
+    }
+
+    #[test]
+    fn test_normalize_release_type() {
+        for (actual, expected) in [
+            ("3.9.9", "ga"),
+            ("4.0.0-alpha-13", "ea"),
+            ("4.0.0-beta-5", "ea"),
+            ("4.0.0-rc-2", "ea"),
+        ] {
+            assert_eq!(normalize_release_type(actual), expected);
+        }
+    }
+}
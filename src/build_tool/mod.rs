@@ -0,0 +1,80 @@
+use std::{
+    collections::HashSet,
+    hash::{Hash, Hasher},
+    sync::{Arc, LazyLock},
+};
+
+use eyre::Result;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::http::HTTP;
+
+pub mod gradle;
+pub mod maven;
+
+pub static BUILD_TOOLS: LazyLock<Vec<Arc<dyn BuildTool>>> =
+    LazyLock::new(|| vec![Arc::new(gradle::Gradle {}), Arc::new(maven::Maven {})]);
+
+/// A distribution archive of a build tool e.g. gradle-8.5-bin.zip
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+pub struct BuildToolData {
+    pub checksum: Option<String>,
+    pub checksum_url: Option<String>,
+    pub file_type: String,
+    pub filename: String,
+    pub release_type: String,
+    pub size: Option<i32>,
+    pub tool: String,
+    pub url: String,
+    pub version: String,
+}
+
+// ensure this matches the PRIMARY KEY of the BUILD_TOOL table
+impl Hash for BuildToolData {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.url.hash(state);
+    }
+}
+
+// ensure this matches the PRIMARY KEY of the BUILD_TOOL table
+impl PartialEq for BuildToolData {
+    fn eq(&self, other: &Self) -> bool {
+        self.url == other.url
+    }
+}
+
+impl Eq for BuildToolData {}
+
+/// Represents a build tool whose distributions are tracked next to the JVMs
+pub trait BuildTool: Send + Sync {
+    /// Returns the name of the build tool
+    fn get_name(&self) -> String;
+
+    /// Fetches the data of all available distributions of the build tool
+    fn fetch(&self) -> Result<HashSet<BuildToolData>> {
+        let mut data = HashSet::new();
+        let start = std::time::Instant::now();
+        self.fetch_data(&mut data)?;
+
+        info!(
+            "[{}] fetched {} entries in {:.2} seconds",
+            self.get_name(),
+            data.len(),
+            start.elapsed().as_secs_f32()
+        );
+        Ok(data)
+    }
+
+    /// Fetches the data of all available distributions of the build tool
+    fn fetch_data(&self, data: &mut HashSet<BuildToolData>) -> Result<()>;
+}
+
+/// Returns the checksum of the first checksum URL which is available in form of `{algorithm}:{digest}`
+fn fetch_checksum(urls: &[(&str, String)]) -> Option<(String, String)> {
+    urls.iter().find_map(|(algorithm, url)| {
+        let body = HTTP.get_text(url).ok()?;
+        let digest = body.split_whitespace().next()?;
+        Some((format!("{}:{}", algorithm, digest), url.clone()))
+    })
+}
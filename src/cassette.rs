@@ -0,0 +1,153 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use eyre::Result;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+static CASSETTE: OnceLock<Cassette> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CassetteMode {
+    /// Sends requests to the vendors and records the responses
+    Record,
+    /// Replays recorded responses without sending any request
+    Replay,
+}
+
+/// A directory of recorded HTTP responses, one JSON file per request method and URL
+///
+/// Cassettes allow running `fetch` against recorded vendor responses e.g. in CI. They are updated intentionally by
+/// running `fetch --record`.
+#[derive(Clone, Debug)]
+pub struct Cassette {
+    pub mode: CassetteMode,
+    path: PathBuf,
+}
+
+/// A recorded HTTP response
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Recording {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl Cassette {
+    pub fn new(mode: CassetteMode, path: &Path) -> Self {
+        Cassette {
+            mode,
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Returns the cassette used by the HTTP client if any
+    pub fn get() -> Option<&'static Cassette> {
+        CASSETTE.get()
+    }
+
+    /// Uses the cassette for all subsequent HTTP requests
+    pub fn insert(self) -> Result<()> {
+        if self.mode == CassetteMode::Record {
+            std::fs::create_dir_all(&self.path)?;
+        }
+        CASSETTE
+            .set(self)
+            .map_err(|_| eyre::eyre!("a cassette is already in use"))
+    }
+
+    /// Returns the recorded response for a request e.g. `GET https://example.com`
+    pub fn load(&self, method: &str, url: &str) -> Result<Recording> {
+        let path = self.file(method, url);
+        let file = std::fs::File::open(&path).map_err(|e| {
+            eyre::eyre!(
+                "no recorded response for {} {} in {}: {}",
+                method,
+                url,
+                path.display(),
+                e
+            )
+        })?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Records the response for a request
+    pub fn save(&self, recording: &Recording) -> Result<()> {
+        let file = std::fs::File::create(self.file(&recording.method, &recording.url))?;
+        serde_json::to_writer_pretty(file, recording)?;
+        Ok(())
+    }
+
+    fn file(&self, method: &str, url: &str) -> PathBuf {
+        self.path
+            .join(format!("{:016x}.json", fnv1a(&format!("{} {}", method, url))))
+    }
+}
+
+impl Recording {
+    pub fn header_map(&self) -> HeaderMap {
+        self.headers
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// FNV-1a hash which, unlike the std hasher, is stable across Rust releases
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("roast-cassette-{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let cassette = Cassette::new(CassetteMode::Replay, &path);
+        let recording = Recording {
+            method: "GET".to_string(),
+            url: "https://api.adoptium.net/v3/info/available_releases".to_string(),
+            status: 200,
+            headers: BTreeMap::from([("link".to_string(), "<https://example.com>; rel=\"next\"".to_string())]),
+            body: "{}".to_string(),
+        };
+
+        cassette.save(&recording).unwrap();
+
+        assert_eq!(cassette.load("GET", &recording.url).unwrap(), recording);
+        assert_eq!(
+            cassette
+                .load("GET", &recording.url)
+                .unwrap()
+                .header_map()
+                .get("link")
+                .unwrap(),
+            "<https://example.com>; rel=\"next\""
+        );
+        assert!(cassette.load("HEAD", &recording.url).is_err());
+        assert!(cassette.load("GET", "https://example.com/unknown").is_err());
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+}
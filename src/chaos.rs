@@ -0,0 +1,115 @@
+use std::{
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use eyre::Result;
+use log::debug;
+
+/// Rate of injected HTTP faults, only set by `fetch --chaos`
+static RATE: OnceLock<f64> = OnceLock::new();
+static STATE: AtomicU64 = AtomicU64::new(0);
+static FAULTS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, PartialEq)]
+enum Fault {
+    Failure,
+    Timeout,
+}
+
+/// Seed of deterministic runs, which inject the same faults every time
+const FIXED_SEED: u64 = 0x5eed;
+
+/// Enables the injection of random HTTP faults with the given rate between 0 and 1
+pub fn enable(rate: f64, deterministic: bool) -> Result<()> {
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(eyre::eyre!("chaos rate must be between 0 and 1: {}", rate));
+    }
+    let seed = match deterministic {
+        true => FIXED_SEED,
+        false => SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+    };
+    STATE.store(seed | 1, Ordering::Relaxed);
+    RATE.set(rate).map_err(|_| eyre::eyre!("chaos mode is already enabled"))
+}
+
+/// Returns the number of faults injected so far
+pub fn faults() -> u64 {
+    FAULTS.load(Ordering::Relaxed)
+}
+
+/// Fails a request at random if chaos mode is enabled
+pub fn inject(url: &str) -> Result<()> {
+    let Some(rate) = RATE.get() else {
+        return Ok(());
+    };
+    match fault(next_random(), *rate) {
+        Some(fault) => {
+            FAULTS.fetch_add(1, Ordering::Relaxed);
+            debug!("chaos: injecting {:?} for {}", fault, url);
+            if fault == Fault::Timeout {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(eyre::eyre!("chaos: injected {:?} for {}", fault, url).wrap_err("request failed"))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Returns the fault to inject for a random roll in [0, 1)
+fn fault(roll: f64, rate: f64) -> Option<Fault> {
+    match roll {
+        r if r < rate / 2.0 => Some(Fault::Timeout),
+        r if r < rate => Some(Fault::Failure),
+        _ => None,
+    }
+}
+
+/// xorshift64* random number in [0, 1)
+fn next_random() -> f64 {
+    let next = |mut x: u64| {
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        x
+    };
+    let state = STATE
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(next(x)))
+        .unwrap_or_default();
+    (next(state).wrapping_mul(0x2545f4914f6cdd1d) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fault() {
+        for (roll, rate, expected) in [
+            (0.0, 0.0, None),
+            (0.5, 0.0, None),
+            (0.01, 0.1, Some(Fault::Timeout)),
+            (0.07, 0.1, Some(Fault::Failure)),
+            (0.1, 0.1, None),
+            (0.99, 1.0, Some(Fault::Failure)),
+        ] {
+            assert_eq!(fault(roll, rate), expected, "roll {} with rate {}", roll, rate);
+        }
+    }
+
+    #[test]
+    fn test_next_random() {
+        STATE.store(42, Ordering::Relaxed);
+        for _ in 0..1000 {
+            assert!((0.0..1.0).contains(&next_random()));
+        }
+    }
+
+    #[test]
+    fn test_inject_disabled() {
+        assert!(inject("https://example.com").is_ok());
+    }
+}
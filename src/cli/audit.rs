@@ -0,0 +1,448 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+use eyre::Result;
+use log::{error, info, warn};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    config::Conf,
+    db::{jvm_repository::JvmRepository, merged_jvm_repository::MergedJvmRepository, pool::ConnectionPool},
+    http::HTTP,
+    jvm::OPT_IN,
+    script::RowScript,
+};
+
+use super::export::{LinkVerifier, Paging, VerifyLinks, export_rows, get_export_filter_map};
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Exports(Exports),
+    Foojay(Foojay),
+}
+
+/// Verify published data
+#[derive(Debug, clap::Args)]
+pub struct Audit {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+impl Audit {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Commands::Exports(cmd) => cmd.run(),
+            Commands::Foojay(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Verify that exported files match the database
+///
+/// Re-creates the export of a {key}/{os}/{arch}.json layout from the database and reports every file which
+/// is missing, stale or differs from it e.g. after manual edits or partial uploads. Include, exclude, filters and
+/// --verify-links must match the options of the export.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Exports {
+    /// Path of the exported files. Default: export.path
+    #[clap(long, value_name = "PATH")]
+    pub path: Option<PathBuf>,
+    /// Directory layout of the export, one of release_type, vendor
+    #[clap(long, value_name = "LAYOUT", default_value = "vendor")]
+    pub layout: String,
+    /// Properties e.g.: architecture, os, vendor, version
+    #[clap(short = 'i', long, num_args = 0.., value_delimiter = ',', value_name = "PROPERTY")]
+    pub include: Option<Vec<String>>,
+    /// Properties e.g.: architecture, os, vendor, version
+    #[clap(short = 'e', long, num_args = 0.., value_delimiter = ',', value_name = "PROPERTY")]
+    pub exclude: Option<Vec<String>>,
+    /// Filters to apply to the data e.g.: file_type=tar.gz,zip&features=musl,javafx,lite
+    #[clap(short = 'f', long, num_args = 0.., value_delimiter = '&', value_name = "FILTER")]
+    pub filters: Option<Vec<String>>,
+    /// Verify the download URLs by HEAD requests and drop or flag the records of dead URLs
+    #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "drop")]
+    pub verify_links: Option<VerifyLinks>,
+    /// Maximum number of HEAD requests of --verify-links, remaining URLs are exported unchecked
+    #[clap(long, value_name = "COUNT", default_value = "1000")]
+    pub link_budget: usize,
+    #[clap(flatten)]
+    pub paging: Paging,
+}
+
+/// Difference between an exported file and the database
+#[derive(Debug, PartialEq)]
+struct Drift {
+    file: String,
+    exists: bool,
+    only_in_db: usize,
+    only_in_file: usize,
+}
+
+impl Exports {
+    pub fn run(self) -> Result<()> {
+        let conf = Conf::try_get()?;
+        let path = match (&self.path, &conf.export.path) {
+            (Some(path), _) => path.clone(),
+            (None, Some(path)) => PathBuf::from(path),
+            (None, None) => return Err(eyre::eyre!("export.path is not configured")),
+        };
+        let include = self.include.unwrap_or_default();
+        let exclude = self.exclude.unwrap_or_default();
+        let filters = get_export_filter_map(self.filters.unwrap_or_default());
+        let script = RowScript::load(&conf)?;
+        let verifier = LinkVerifier::new(self.verify_links, self.link_budget)?;
+        let page = self.paging.page();
+
+        // the files are re-created from the same sources and by the same function as the export
+        let db = MergedJvmRepository::new(ConnectionPool::get_export_pools()?)?;
+        let column = match self.layout.as_str() {
+            "release_type" | "vendor" => self.layout.as_str(),
+            _ => return Err(eyre::eyre!("unsupported layout: {}", self.layout)),
+        };
+        let oses = db.get_distinct("os")?;
+        let archs = db.get_distinct("architecture")?;
+        let mut expected: BTreeMap<String, Vec<Map<String, Value>>> = BTreeMap::new();
+        for key in db.get_distinct(column)? {
+            for os in &oses {
+                for arch in &archs {
+                    let data = match column {
+                        "release_type" => db.export_release_type(&key, arch, os)?,
+                        _ => db.export_vendor(&key, os, arch)?,
+                    };
+                    let (_, rows) = export_rows(data, &filters, &page, &include, &exclude, &verifier, &script)?;
+                    expected.insert(format!("{}/{}/{}.json", key, os, arch), rows);
+                }
+            }
+        }
+
+        let mut actual = BTreeMap::new();
+        for file in exported_files(&path)? {
+            let rows: Vec<Map<String, Value>> = serde_json::from_reader(File::open(path.join(&file))?)
+                .map_err(|e| eyre::eyre!("unable to parse {}: {}", file, e))?;
+            actual.insert(file, rows);
+        }
+
+        let drifts = drifts(&expected, &actual);
+        for drift in &drifts {
+            match drift.exists {
+                true => error!(
+                    "{}: {} record(s) missing, {} record(s) not in the database",
+                    drift.file, drift.only_in_db, drift.only_in_file
+                ),
+                false => error!("{}: file is missing ({} records)", drift.file, drift.only_in_db),
+            }
+        }
+        match drifts.len() {
+            0 => {
+                info!("all {} exported files match the database", actual.len());
+                Ok(())
+            }
+            n => Err(eyre::eyre!(
+                "{} of {} file(s) differ from the database",
+                n,
+                actual.len()
+            )),
+        }
+    }
+}
+
+/// Vendors and their distribution in the Foojay disco API
+const FOOJAY_DISTRIBUTIONS: &[(&str, &str)] = &[
+    ("corretto", "corretto"),
+    ("dragonwell", "dragonwell"),
+    ("jetbrains", "jetbrains"),
+    ("kona", "kona"),
+    ("liberica", "liberica"),
+    ("mandrel", "mandrel"),
+    ("microsoft", "microsoft"),
+    ("oracle", "oracle"),
+    ("sapmachine", "sap_machine"),
+    ("semeru", "semeru"),
+    ("temurin", "temurin"),
+    ("trava", "trava"),
+    ("zulu", "zulu"),
+];
+
+/// Compare the catalog with the Foojay disco API
+///
+/// Queries the GA packages of a sample of vendor/major/os/arch combinations from https://api.foojay.io and reports
+/// the files Foojay knows but the database lacks and vice versa, which points to gaps of the vendor parsers.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Foojay {
+    /// Vendors to compare e.g.: temurin, zulu. Default: all vendors known by Foojay
+    #[clap(long, num_args = 0.., value_delimiter = ',', value_name = "VENDOR")]
+    pub vendors: Option<Vec<String>>,
+    /// Major versions to compare e.g.: 17, 21
+    #[clap(long, num_args = 0.., value_delimiter = ',', value_name = "MAJOR", default_value = "21")]
+    pub majors: Vec<i32>,
+    /// Operating systems to compare e.g.: linux, macosx, windows
+    #[clap(long, num_args = 0.., value_delimiter = ',', value_name = "OS", default_value = "linux,macosx,windows")]
+    pub os: Vec<String>,
+    /// Architectures to compare e.g.: aarch64, x86_64
+    #[clap(long, num_args = 0.., value_delimiter = ',', value_name = "ARCH", default_value = "aarch64,x86_64")]
+    pub arch: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FoojayResponse {
+    result: Vec<FoojayPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FoojayPackage {
+    filename: String,
+}
+
+impl Foojay {
+    pub fn run(self) -> Result<()> {
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = JvmRepository::new(conn_pool)?;
+        let distributions = FOOJAY_DISTRIBUTIONS
+            .iter()
+            .filter(|(vendor, _)| self.vendors.as_ref().is_none_or(|v| v.iter().any(|v| v == vendor)))
+            .collect::<Vec<_>>();
+        if distributions.is_empty() {
+            return Err(eyre::eyre!("none of the vendors is known by Foojay"));
+        }
+
+        let (mut missing, mut extra) = (0, 0);
+        for (vendor, distribution) in distributions {
+            for major in &self.majors {
+                for os in &self.os {
+                    for arch in &self.arch {
+                        let ours = db
+                            .export_vendor_major(vendor, *major, os, arch)?
+                            .into_iter()
+                            .filter(|item| item.release_type == "ga")
+                            .map(|item| item.filename)
+                            .collect::<BTreeSet<String>>();
+                        let url = foojay_url(distribution, *major, os, arch);
+                        let theirs = match HTTP.get_json::<FoojayResponse, _>(url.as_str()) {
+                            Ok(resp) => resp
+                                .result
+                                .into_iter()
+                                .map(|p| p.filename)
+                                .collect::<BTreeSet<String>>(),
+                            Err(err) => {
+                                warn!(
+                                    "[{}] unable to query Foojay for {}/{}/{}: {}",
+                                    vendor, major, os, arch, err
+                                );
+                                continue;
+                            }
+                        };
+                        let combination = format!("{}/{}/{}/{}", vendor, major, os, arch);
+                        for file in theirs.difference(&ours) {
+                            warn!("{}: {} is missing, Foojay knows it", combination, file);
+                            missing += 1;
+                        }
+                        for file in ours.difference(&theirs) {
+                            info!("{}: {} is unknown to Foojay", combination, file);
+                            extra += 1;
+                        }
+                    }
+                }
+            }
+        }
+        info!("{} file(s) missing, {} file(s) unknown to Foojay", missing, extra);
+        Ok(())
+    }
+}
+
+/// Returns the disco API URL of the GA packages of a distribution, major version, os and architecture
+fn foojay_url(distribution: &str, major: i32, os: &str, arch: &str) -> String {
+    let os = match os {
+        "macosx" => "macos",
+        os => os,
+    };
+    let arch = match arch {
+        "x86_64" => "x64",
+        arch => arch,
+    };
+    format!(
+        "https://api.foojay.io/disco/v3.0/packages?distribution={}&jdk_version={}&operating_system={}&architecture={}&release_status=ga",
+        distribution, major, os, arch
+    )
+}
+
+/// Returns the paths of the exported files in form of {key}/{os}/{arch}.json relative to the export path
+fn exported_files(path: &Path) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for key in std::fs::read_dir(path)?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+    {
+        for os in std::fs::read_dir(key.path())?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+        {
+            for arch in std::fs::read_dir(os.path())?.filter_map(|e| e.ok()) {
+                let name = arch.file_name().to_string_lossy().to_string();
+                if name.ends_with(".json") && arch.path().is_file() {
+                    files.push(format!(
+                        "{}/{}/{}",
+                        key.file_name().to_string_lossy(),
+                        os.file_name().to_string_lossy(),
+                        name
+                    ));
+                }
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Compares the expected and the exported rows of every file regardless of their order
+///
+/// Properties of [`OPT_IN`] change daily without the data and are not compared
+fn drifts(
+    expected: &BTreeMap<String, Vec<Map<String, Value>>>,
+    actual: &BTreeMap<String, Vec<Map<String, Value>>>,
+) -> Vec<Drift> {
+    let canonical = |rows: Option<&Vec<Map<String, Value>>>| -> BTreeSet<String> {
+        rows.into_iter()
+            .flatten()
+            .map(|row| {
+                let mut row = row.clone();
+                row.retain(|prop, _| !OPT_IN.contains(&prop.as_str()));
+                serde_json::to_string(&row).unwrap_or_default()
+            })
+            .collect()
+    };
+    expected
+        .keys()
+        .chain(actual.keys())
+        .collect::<BTreeSet<&String>>()
+        .into_iter()
+        .filter_map(|file| {
+            let db = canonical(expected.get(file));
+            let exported = canonical(actual.get(file));
+            let drift = Drift {
+                file: file.clone(),
+                exists: actual.contains_key(file),
+                only_in_db: db.difference(&exported).count(),
+                only_in_file: exported.difference(&db).count(),
+            };
+            (drift.only_in_db > 0 || drift.only_in_file > 0).then_some(drift)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_foojay_url() {
+        assert_eq!(
+            foojay_url("sap_machine", 21, "macosx", "x86_64"),
+            "https://api.foojay.io/disco/v3.0/packages?distribution=sap_machine&jdk_version=21&operating_system=macos&architecture=x64&release_status=ga"
+        );
+        assert_eq!(
+            foojay_url("temurin", 17, "linux", "aarch64"),
+            "https://api.foojay.io/disco/v3.0/packages?distribution=temurin&jdk_version=17&operating_system=linux&architecture=aarch64&release_status=ga"
+        );
+    }
+
+    #[test]
+    fn test_foojay_response() {
+        let resp: FoojayResponse = serde_json::from_value(json!({
+            "result": [
+                {"id": "abc", "filename": "OpenJDK21U-jdk_x64_linux_hotspot_21.0.5_11.tar.gz", "distribution": "temurin"}
+            ],
+            "message": ""
+        }))
+        .unwrap();
+        assert_eq!(
+            resp.result[0].filename,
+            "OpenJDK21U-jdk_x64_linux_hotspot_21.0.5_11.tar.gz"
+        );
+    }
+
+    fn get_row(url: &str, version: &str) -> Map<String, Value> {
+        let mut row = Map::new();
+        row.insert("url".to_string(), json!(url));
+        row.insert("version".to_string(), json!(version));
+        row
+    }
+
+    #[test]
+    fn test_drifts() {
+        let expected = BTreeMap::from([
+            (
+                "temurin/linux/x86_64.json".to_string(),
+                vec![
+                    get_row("https://example.com/a", "21.0.2"),
+                    get_row("https://example.com/b", "21.0.1"),
+                ],
+            ),
+            (
+                "zulu/linux/x86_64.json".to_string(),
+                vec![get_row("https://example.com/c", "21.0.2")],
+            ),
+            (
+                "corretto/linux/x86_64.json".to_string(),
+                vec![get_row("https://example.com/d", "21.0.2")],
+            ),
+        ]);
+        let mut actual = BTreeMap::from([
+            (
+                "temurin/linux/x86_64.json".to_string(),
+                vec![
+                    get_row("https://example.com/b", "21.0.1"),
+                    get_row("https://example.com/a", "21.0.2"),
+                ],
+            ),
+            (
+                "zulu/linux/x86_64.json".to_string(),
+                vec![get_row("https://example.com/c", "21.0.1")],
+            ),
+            ("zulu/windows/x86_64.json".to_string(), vec![]),
+        ]);
+        // age_days of an export on another day does not drift
+        for row in actual.get_mut("temurin/linux/x86_64.json").unwrap() {
+            row.insert("age_days".to_string(), json!(3));
+        }
+
+        assert_eq!(
+            drifts(&expected, &actual),
+            vec![
+                Drift {
+                    file: "corretto/linux/x86_64.json".to_string(),
+                    exists: false,
+                    only_in_db: 1,
+                    only_in_file: 0,
+                },
+                Drift {
+                    file: "zulu/linux/x86_64.json".to_string(),
+                    exists: true,
+                    only_in_db: 1,
+                    only_in_file: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exported_files() {
+        let path = std::env::temp_dir().join(format!("roast-audit-{}", std::process::id()));
+        std::fs::create_dir_all(path.join("temurin/linux")).unwrap();
+        std::fs::write(path.join("temurin/linux/x86_64.json"), "[]").unwrap();
+        std::fs::write(path.join("temurin/linux/README.md"), "").unwrap();
+        std::fs::write(path.join("index.json"), "[]").unwrap();
+
+        assert_eq!(exported_files(&path).unwrap(), vec!["temurin/linux/x86_64.json"]);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
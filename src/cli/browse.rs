@@ -0,0 +1,319 @@
+use std::io::Write;
+
+use eyre::Result;
+use openssl::base64;
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Row, Table, TableState},
+};
+
+use crate::{
+    db::{
+        jvm_repository::{JvmRepository, Page, Sort},
+        pool::ConnectionPool,
+    },
+    jvm::JvmData,
+};
+
+use super::export::get_filter_map;
+
+/// Columns of the table, the visible ones by default are marked true
+const COLUMNS: &[(&str, bool)] = &[
+    ("vendor", true),
+    ("version", true),
+    ("os", true),
+    ("architecture", true),
+    ("image_type", true),
+    ("file_type", true),
+    ("release_type", true),
+    ("features", false),
+    ("checksum", false),
+    ("url", false),
+];
+
+const HELP: &str = "/ search  1-9,0 toggle column  u copy url  c copy checksum  q quit";
+
+/// Browse JVM data interactively
+///
+/// Loads the records matching the filters and shows them in a table, requires the `tui` feature.
+///
+/// Keys:
+///   /           fuzzy search by vendor and version e.g.: tem 21.0
+///   up/down     select a record, pgup/pgdn skip 20 records
+///   1-9, 0      toggle the columns
+///   u           copy the URL of the selected record to the clipboard
+///   c           copy the checksum of the selected record to the clipboard
+///   q, esc      quit
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Browse {
+    /// Filters in form of key=value1,value2 or key=!value e.g.: vendor=temurin os=linux
+    #[clap(value_name = "FILTER")]
+    pub filters: Vec<String>,
+}
+
+impl Browse {
+    pub fn run(self) -> Result<()> {
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = JvmRepository::new(conn_pool)?;
+        let page = Page {
+            sort: ["vendor:asc", "version:desc"]
+                .iter()
+                .map(|s| s.parse::<Sort>().map_err(eyre::Error::msg))
+                .collect::<Result<_>>()?,
+            ..Default::default()
+        };
+        let data = db.query(&get_filter_map(self.filters), &page)?;
+
+        let mut terminal = ratatui::init();
+        let result = App::new(data).run(&mut terminal);
+        ratatui::restore();
+        result
+    }
+}
+
+struct App {
+    data: Vec<JvmData>,
+    /// Indices of the records matching the search
+    matches: Vec<usize>,
+    columns: Vec<(&'static str, bool)>,
+    search: String,
+    searching: bool,
+    status: String,
+    state: TableState,
+}
+
+impl App {
+    fn new(data: Vec<JvmData>) -> Self {
+        let mut app = App {
+            data,
+            matches: Vec::new(),
+            columns: COLUMNS.to_vec(),
+            search: String::new(),
+            searching: false,
+            status: HELP.to_string(),
+            state: TableState::default(),
+        };
+        app.update_matches();
+        app
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key.code)?
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Handles a key press, returns false to quit
+    fn handle_key(&mut self, code: KeyCode) -> Result<bool> {
+        if self.searching {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => self.searching = false,
+                KeyCode::Backspace => {
+                    self.search.pop();
+                    self.update_matches();
+                }
+                KeyCode::Char(c) => {
+                    self.search.push(c);
+                    self.update_matches();
+                }
+                _ => {}
+            }
+            return Ok(true);
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::PageDown => self.select(20),
+            KeyCode::PageUp => self.select(-20),
+            KeyCode::Char('u') => self.copy("url", |item| Some(item.url.clone()))?,
+            KeyCode::Char('c') => self.copy("checksum", |item| item.checksum.clone())?,
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                // 1 toggles the first column, 0 the tenth
+                let index = (c.to_digit(10).unwrap() as usize + 9) % 10;
+                if let Some(column) = self.columns.get_mut(index) {
+                    column.1 = !column.1;
+                }
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = self
+            .data
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| search_matches(&self.search, &format!("{} {}", item.vendor, item.version)))
+            .map(|(i, _)| i)
+            .collect();
+        self.state.select(if self.matches.is_empty() { None } else { Some(0) });
+    }
+
+    fn select(&mut self, offset: i64) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default() as i64 + offset;
+        self.state
+            .select(Some(selected.clamp(0, self.matches.len() as i64 - 1) as usize));
+    }
+
+    fn selected(&self) -> Option<&JvmData> {
+        self.state
+            .selected()
+            .and_then(|i| self.matches.get(i))
+            .map(|i| &self.data[*i])
+    }
+
+    /// Copies a property of the selected record to the clipboard by the OSC 52 escape sequence of the terminal
+    fn copy<F>(&mut self, name: &str, value: F) -> Result<()>
+    where
+        F: Fn(&JvmData) -> Option<String>,
+    {
+        self.status = match self.selected().and_then(value) {
+            Some(value) => {
+                let mut stdout = std::io::stdout();
+                write!(stdout, "\x1b]52;c;{}\x07", base64::encode_block(value.as_bytes()))?;
+                stdout.flush()?;
+                format!("copied {}: {}", name, value)
+            }
+            None => format!("no {} to copy", name),
+        };
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [search_area, table_area, status_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        let cursor = if self.searching { "_" } else { "" };
+        frame.render_widget(
+            Line::from(format!(
+                "search: {}{}  ({}/{} records)",
+                self.search,
+                cursor,
+                self.matches.len(),
+                self.data.len()
+            )),
+            search_area,
+        );
+
+        let columns = self
+            .columns
+            .iter()
+            .filter(|(_, visible)| *visible)
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        let rows = self
+            .matches
+            .iter()
+            .map(|i| Row::new(columns.iter().map(|column| column_value(&self.data[*i], column))));
+        let widths = columns.iter().map(|column| match *column {
+            "checksum" | "url" => Constraint::Fill(3),
+            _ => Constraint::Fill(1),
+        });
+        let table = Table::new(rows, widths)
+            .header(Row::new(columns.clone()).style(Style::new().add_modifier(Modifier::BOLD)))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, table_area, &mut self.state);
+
+        frame.render_widget(Line::from(self.status.as_str()), status_area);
+    }
+}
+
+/// Returns the value of a column of a record
+fn column_value(item: &JvmData, column: &str) -> String {
+    match column {
+        "vendor" => item.vendor.clone(),
+        "version" => item.version.clone(),
+        "os" => item.os.clone(),
+        "architecture" => item.architecture.clone(),
+        "image_type" => item.image_type.clone(),
+        "file_type" => item.file_type.clone(),
+        "release_type" => item.release_type.clone(),
+        "features" => item.features.clone().unwrap_or_default().join(","),
+        "checksum" => item.checksum.clone().unwrap_or_default(),
+        "url" => item.url.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Returns true if every term of the search is a fuzzy match of the text
+fn search_matches(search: &str, text: &str) -> bool {
+    let text = text.to_lowercase();
+    search
+        .split_whitespace()
+        .all(|term| fuzzy_matches(&term.to_lowercase(), &text))
+}
+
+/// Returns true if the characters of the pattern appear in the text in the same order e.g. `tmr` in `temurin`
+fn fuzzy_matches(pattern: &str, text: &str) -> bool {
+    let mut chars = text.chars();
+    pattern.chars().all(|p| chars.any(|c| c == p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_matches() {
+        for (search, text, expected) in [
+            ("", "temurin 21.0.5+11", true),
+            ("tem", "temurin 21.0.5+11", true),
+            ("tmr", "temurin 21.0.5+11", true),
+            ("TEM 21.0", "temurin 21.0.5+11", true),
+            ("zulu", "temurin 21.0.5+11", false),
+            ("temurin 17", "temurin 21.0.5+11", false),
+            ("nirumet", "temurin 21.0.5+11", false),
+        ] {
+            assert_eq!(search_matches(search, text), expected, "{} in {}", search, text);
+        }
+    }
+
+    #[test]
+    fn test_handle_key() {
+        let data = ["corretto", "temurin", "zulu"]
+            .iter()
+            .map(|vendor| JvmData {
+                url: format!("https://example.com/{}.tar.gz", vendor),
+                vendor: vendor.to_string(),
+                version: "21.0.5".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let mut app = App::new(data);
+        assert_eq!(app.matches.len(), 3);
+
+        for code in [
+            KeyCode::Char('/'),
+            KeyCode::Char('t'),
+            KeyCode::Char('m'),
+            KeyCode::Enter,
+        ] {
+            assert!(app.handle_key(code).unwrap());
+        }
+        assert_eq!(app.selected().map(|item| item.vendor.as_str()), Some("temurin"));
+
+        assert!(app.handle_key(KeyCode::Char('1')).unwrap());
+        assert!(!app.columns[0].1);
+        assert!(app.handle_key(KeyCode::Char('0')).unwrap());
+        assert!(app.columns[9].1);
+
+        assert!(!app.handle_key(KeyCode::Char('q')).unwrap());
+    }
+}
@@ -0,0 +1,85 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use eyre::Result;
+
+use crate::config::Conf;
+
+/// Confirmation of destructive operations e.g. deleting records, flattened into the arguments of the command
+#[derive(Debug, Default, clap::Args)]
+pub struct Confirm {
+    /// Skip the interactive confirmation of destructive operations
+    #[clap(short = 'y', long)]
+    pub yes: bool,
+}
+
+impl Confirm {
+    /// Fails unless the destructive operation is confirmed
+    ///
+    /// Operations are refused entirely if `database.protected` is set. Otherwise they are confirmed by `--yes` or
+    /// interactively if standard input is a terminal.
+    pub fn confirm(&self, operation: &str) -> Result<()> {
+        let protected = Conf::try_get()?.database.protected.unwrap_or(false);
+        let answer = match protected || self.yes || !std::io::stdin().is_terminal() {
+            true => None,
+            false => Some(prompt(operation)?),
+        };
+        confirmed(operation, protected, self.yes, answer.as_deref())
+    }
+}
+
+fn prompt(operation: &str) -> Result<String> {
+    eprint!("{}? [y/N] ", operation);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer)
+}
+
+/// Decides whether an operation may run, `answer` is the reply to the interactive prompt if there was one
+fn confirmed(operation: &str, protected: bool, yes: bool, answer: Option<&str>) -> Result<()> {
+    if protected {
+        return Err(eyre::eyre!(
+            "refusing to {}: the database is protected (database.protected)",
+            operation
+        ));
+    }
+    if yes {
+        return Ok(());
+    }
+    match answer.map(|a| a.trim().to_lowercase()) {
+        Some(a) if a == "y" || a == "yes" => Ok(()),
+        Some(_) => Err(eyre::eyre!("{} aborted", operation)),
+        None => Err(eyre::eyre!(
+            "{} requires --yes when not running interactively",
+            operation
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmed() {
+        for (protected, yes, answer, expected) in [
+            (true, true, None, false),
+            (true, false, Some("y"), false),
+            (false, true, None, true),
+            (false, false, Some("y\n"), true),
+            (false, false, Some("YES"), true),
+            (false, false, Some("n"), false),
+            (false, false, Some(""), false),
+            (false, false, None, false),
+        ] {
+            assert_eq!(
+                confirmed("prune", protected, yes, answer).is_ok(),
+                expected,
+                "protected {} yes {} answer {:?}",
+                protected,
+                yes,
+                answer
+            );
+        }
+    }
+}
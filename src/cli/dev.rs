@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+use eyre::Result;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cassette::{Cassette, CassetteMode},
+    jvm::{
+        JvmData,
+        vendor::{self, VENDORS},
+    },
+    output,
+};
+
+/// File of a cassette directory with the fixture of every recorded vendor
+const FETCHED: &str = "fetched.json";
+
+/// The fetch of a vendor recorded in a cassette directory
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(super) struct Fixture {
+    /// Number of the newest versions or pages fetched
+    pub limit: usize,
+    /// Number of records fetched
+    pub records: u64,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    CheckFixtures(CheckFixtures),
+    RecordFixtures(RecordFixtures),
+}
+
+/// Maintainer tools
+#[derive(Debug, clap::Args)]
+#[clap(hide = true)]
+pub struct Dev {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+impl Dev {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Commands::CheckFixtures(cmd) => cmd.run(),
+            Commands::RecordFixtures(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Record the responses of a small live sample of a vendor as test fixtures
+///
+/// Fetches the newest versions or pages of the vendor and records the raw HTTP responses to the cassette in {output},
+/// which keeps the parser tests aligned with the current naming conventions of the vendor. The number of fetched
+/// records is written to {output}/fetched.json, `dev check-fixtures` expects the replayed fetch to return the same number.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct RecordFixtures {
+    /// Vendor to fetch e.g.: liberica
+    #[clap(long, value_name = "VENDOR")]
+    pub vendor: String,
+    /// Number of the newest versions or pages to fetch
+    #[clap(long, value_name = "COUNT", default_value = "1")]
+    pub limit: usize,
+    /// Cassette directory to record the responses to
+    #[clap(long, value_name = "DIR", default_value = "tests/cassettes")]
+    pub output: PathBuf,
+}
+
+impl RecordFixtures {
+    pub fn run(self) -> Result<()> {
+        let vendor = VENDORS
+            .iter()
+            .find(|v| v.get_name() == self.vendor)
+            .ok_or_else(|| eyre::eyre!("unknown vendor: {}", self.vendor))?;
+        if self.limit == 0 {
+            return Err(eyre::eyre!("--limit must be at least 1"));
+        }
+        Cassette::new(CassetteMode::Record, &self.output).insert()?;
+        vendor::limit_sources(self.limit);
+        info!("[{}] fetching the newest {} sources", self.vendor, self.limit);
+        let data = vendor.fetch()?;
+        if data.is_empty() {
+            return Err(eyre::eyre!("[{}] no records fetched", self.vendor));
+        }
+        let fixture = Fixture {
+            limit: self.limit,
+            records: data.len() as u64,
+        };
+        write_fetched(&self.output, &self.vendor, fixture)?;
+        info!(
+            "[{}] recorded the responses of {} records to {}",
+            self.vendor,
+            data.len(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+/// Replay the test fixtures of all recorded vendors
+///
+/// Fetches every vendor of {dir}/fetched.json from the cassette in {dir} with the limit it was recorded with and fails
+/// if a vendor fails or returns another number of records than recorded. Vendors which are not part of the build are
+/// skipped.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct CheckFixtures {
+    /// Cassette directory to replay the responses from
+    #[clap(long, value_name = "DIR", default_value = "tests/cassettes")]
+    pub dir: PathBuf,
+    /// Write the replayed records to a JSON file
+    #[clap(long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl CheckFixtures {
+    pub fn run(self) -> Result<()> {
+        let fixtures = read_fetched(&self.dir)?;
+        if fixtures.is_empty() {
+            return Err(eyre::eyre!("no fixtures found in {}", self.dir.display()));
+        }
+        Cassette::new(CassetteMode::Replay, &self.dir).insert()?;
+        let mut replayed: Vec<JvmData> = Vec::new();
+        let mut failures = 0;
+        for (name, fixture) in &fixtures {
+            let Some(vendor) = VENDORS.iter().find(|v| &v.get_name() == name) else {
+                warn!("[{}] vendor is not part of this build, skipping", name);
+                continue;
+            };
+            vendor::limit_sources(fixture.limit);
+            match vendor.fetch() {
+                Ok(data) if data.len() as u64 == fixture.records => {
+                    info!("[{}] replayed {} records", name, data.len());
+                    replayed.extend(data);
+                }
+                Ok(data) => {
+                    error!(
+                        "[{}] replayed {} records, recorded {}",
+                        name,
+                        data.len(),
+                        fixture.records
+                    );
+                    failures += 1;
+                }
+                Err(err) => {
+                    error!("[{}] failed to replay: {}", name, err);
+                    failures += 1;
+                }
+            }
+        }
+        if let Some(path) = &self.output {
+            replayed.sort_by(|a, b| a.url.cmp(&b.url));
+            output::write_json(std::fs::File::create(path)?, &replayed, true)?;
+        }
+        match failures {
+            0 => Ok(()),
+            n => Err(eyre::eyre!("{} of {} fixtures failed", n, fixtures.len())),
+        }
+    }
+}
+
+/// Returns the fixtures per vendor of the recorded responses in a cassette directory
+fn read_fetched(dir: &Path) -> Result<BTreeMap<String, Fixture>> {
+    match std::fs::File::open(dir.join(FETCHED)) {
+        Ok(file) => Ok(serde_json::from_reader(file)?),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn write_fetched(dir: &Path, vendor: &str, fixture: Fixture) -> Result<()> {
+    let mut fetched = read_fetched(dir)?;
+    fetched.insert(vendor.to_string(), fixture);
+    let file = std::fs::File::create(dir.join(FETCHED))?;
+    output::write_json(file, &fetched, true)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_fetched() {
+        let dir = std::env::temp_dir().join(format!("roast-fixtures-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(read_fetched(&dir).unwrap().is_empty());
+
+        let fixture = |limit, records| Fixture { limit, records };
+        write_fetched(&dir, "zulu", fixture(1, 2)).unwrap();
+        write_fetched(&dir, "liberica", fixture(3, 3)).unwrap();
+        write_fetched(&dir, "zulu", fixture(1, 4)).unwrap();
+
+        assert_eq!(
+            read_fetched(&dir).unwrap(),
+            BTreeMap::from([
+                ("liberica".to_string(), fixture(3, 3)),
+                ("zulu".to_string(), fixture(1, 4))
+            ])
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
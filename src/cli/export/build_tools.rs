@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use eyre::Result;
+use log::info;
+use serde_json::{Map, Value};
+
+use crate::{
+    build_tool::BuildToolData,
+    config::Conf,
+    db::{build_tool_repository::BuildToolRepository, pool::ConnectionPool},
+    output::Manifest,
+    report::Report,
+};
+
+use super::export_path;
+
+/// Export build tool distributions by build-tools/{tool}
+///
+/// Will export JSON files in form of build-tools/{tool}.json to the path specified in the configuration file
+/// or ROAST_EXPORT_PATH environment variable
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct BuildTools {
+    /// Build tools e.g.: gradle, maven
+    #[clap(short = 't', long, num_args = 0.., value_delimiter = ',', value_name = "TOOL")]
+    pub tools: Option<Vec<String>>,
+    /// Properties e.g.: url, version
+    #[clap(short = 'i', long, num_args = 0.., value_delimiter = ',', value_name = "PROPERTY")]
+    pub include: Option<Vec<String>>,
+    /// Properties e.g.: checksum_url, size
+    #[clap(short = 'e', long, num_args = 0.., value_delimiter = ',', value_name = "PROPERTY")]
+    pub exclude: Option<Vec<String>>,
+    /// Pretty print JSON
+    #[clap(long, default_value = "false")]
+    pub pretty: bool,
+    /// Directory to export to, overrides export.path
+    #[clap(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+}
+
+impl BuildTools {
+    pub fn run(self, report: &Report) -> Result<()> {
+        let conf = Conf::try_get()?;
+        let export_path = export_path(self.output_dir, conf.export.path.as_deref())?;
+        let mut manifest = Manifest::load(&export_path)?;
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = BuildToolRepository::new(conn_pool)?;
+
+        let tools = match self.tools {
+            Some(tools) => tools,
+            None => db.get_tools()?,
+        };
+        let include = self.include.unwrap_or_default();
+        let exclude = self.exclude.unwrap_or_default();
+
+        for tool in &tools {
+            let export_data = db
+                .export_tool(tool)?
+                .iter()
+                .map(|item| map(item, &include, &exclude))
+                .collect::<Result<Vec<Map<String, Value>>>>()?;
+            let size = export_data.len();
+
+            info!("exporting {} records to build-tools/{}.json", size, tool);
+            let path = export_path.join("build-tools").join(format!("{}.json", tool));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            manifest.write_json_file(&path, &export_data, self.pretty, conf.export.brotli)?;
+            report.success(&format!("build-tools/{}.json", tool), size as u64);
+        }
+        manifest.save()?;
+        Ok(())
+    }
+}
+
+fn map(item: &BuildToolData, include: &[String], exclude: &[String]) -> Result<Map<String, Value>> {
+    let Value::Object(mut map) = serde_json::to_value(item)? else {
+        return Err(eyre::eyre!("unable to map {}", item.url));
+    };
+    map.retain(|key, _| (include.is_empty() || include.contains(key)) && !exclude.contains(key));
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        let item = BuildToolData {
+            checksum: Some("sha256:abc".to_string()),
+            file_type: "zip".to_string(),
+            filename: "gradle-8.12-bin.zip".to_string(),
+            release_type: "ga".to_string(),
+            tool: "gradle".to_string(),
+            url: "https://services.gradle.org/distributions/gradle-8.12-bin.zip".to_string(),
+            version: "8.12".to_string(),
+            ..Default::default()
+        };
+
+        let all = map(&item, &[], &["size".to_string()]).unwrap();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all.get("checksum").unwrap(), "sha256:abc");
+        assert!(!all.contains_key("size"));
+
+        let included = map(&item, &["url".to_string(), "version".to_string()], &[]).unwrap();
+        assert_eq!(included.len(), 2);
+        assert_eq!(included.get("version").unwrap(), "8.12");
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use eyre::Result;
+use log::info;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    config::Conf,
+    db::{merged_jvm_repository::MergedJvmRepository, pool::ConnectionPool},
+    jvm::{JvmData, libc},
+    output::Manifest,
+    report::Report,
+    script::{self, RowScript},
+};
+
+use super::{export_path, get_export_filter_map};
+
+/// Export a libc compatibility matrix by {os}/{architecture}
+///
+/// Will export JSON files in form of compatibility/linux/{arch}.json to the path specified in the configuration file
+/// or ROAST_EXPORT_PATH environment variable. Each record contains the `libc` an artifact is linked against and the
+/// `min_glibc_version` if it is documented by the vendor.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Compatibility {
+    /// Architectures e.g.: aarch64, arm32, x86_64
+    #[clap(short = 'a', long, num_args = 0.., value_delimiter = ',', value_name = "ARCH")]
+    pub arch: Option<Vec<String>>,
+    /// Properties to include e.g.: filename, url, vendor, version
+    #[clap(short = 'i', long, num_args = 0.., value_delimiter = ',', value_name = "PROPERTY")]
+    pub include: Option<Vec<String>>,
+    /// Properties to exclude e.g.: checksum, checksum_url, size
+    #[clap(short = 'e', long, num_args = 0.., value_delimiter = ',', value_name = "PROPERTY")]
+    pub exclude: Option<Vec<String>>,
+    /// Filters to apply to the data e.g.: file_type=tar.gz,zip&features=musl,javafx,!lite
+    #[clap(short = 'f', long, num_args = 0.., value_delimiter = '&', value_name = "FILTER")]
+    pub filters: Option<Vec<String>>,
+    /// Pretty print JSON
+    #[clap(long, default_value = "false")]
+    pub pretty: bool,
+    /// Directory to export to, overrides export.path
+    #[clap(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+}
+
+impl Compatibility {
+    pub fn run(self, report: &Report) -> Result<()> {
+        let conf = Conf::try_get()?;
+        let export_path = export_path(self.output_dir, conf.export.path.as_deref())?;
+        let mut manifest = Manifest::load(&export_path)?;
+        let db = MergedJvmRepository::new(ConnectionPool::get_export_pools()?)?;
+
+        let arch_default = db.get_distinct("architecture")?;
+        let archs = self.arch.unwrap_or(arch_default);
+
+        let include = self.include.unwrap_or_default();
+        let exclude = self.exclude.unwrap_or_default();
+
+        let filters = get_export_filter_map(self.filters.unwrap_or_default());
+
+        let script = RowScript::load(&conf)?;
+
+        let os = "linux";
+        for arch in &archs {
+            let data = db.export_os(os, arch)?;
+
+            let export_data = data
+                .into_par_iter()
+                .filter(|item| JvmData::filter(item, &filters))
+                .map(|item| {
+                    let mut map = JvmData::map(&item, &include, &exclude);
+                    map.insert("libc".to_string(), json!(libc::libc(&item)));
+                    map.insert("min_glibc_version".to_string(), json!(libc::min_glibc_version(&item)));
+                    map
+                })
+                .collect::<Vec<Map<String, Value>>>();
+            let export_data = script::transform_rows(&script, export_data)?;
+            let size = export_data.len();
+
+            info!("exporting {} records to compatibility/{}/{}.json", size, os, arch);
+            let path = export_path
+                .join("compatibility")
+                .join(os)
+                .join(format!("{}.json", arch));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            manifest.write_json_file(&path, &export_data, self.pretty, conf.export.brotli)?;
+            report.success(&format!("compatibility/{}/{}.json", os, arch), size as u64);
+        }
+        manifest.save()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use eyre::Result;
+use log::{info, warn};
+use serde_json::{Map, Value};
+
+use crate::{
+    db::{
+        not_found_repository::{NotFoundRepository, PERMANENT_FAILURES, RETRY_DAYS},
+        pool::ConnectionPool,
+    },
+    http::HTTP,
+    jvm::JvmData,
+};
+
+/// What to do with records whose download URL is dead
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum VerifyLinks {
+    /// Remove the records from the export
+    Drop,
+    /// Export the records with `dead_link: true`
+    Flag,
+}
+
+/// Probes the download URLs of exported records by HEAD requests
+///
+/// Every URL is requested at most once per run and URLs which permanently returned 404 during `fetch` are dead
+/// without a request. Once the budget of requests is spent the remaining URLs are exported unchecked.
+pub(crate) struct LinkVerifier {
+    mode: Option<VerifyLinks>,
+    budget: usize,
+    requests: AtomicUsize,
+    unchecked: AtomicUsize,
+    /// URLs known to be dead before the run
+    not_found: HashSet<String>,
+    /// Whether a URL is dead, by URL
+    checked: Mutex<HashMap<String, bool>>,
+    head: fn(&str) -> Result<u16>,
+}
+
+impl LinkVerifier {
+    pub fn new(mode: Option<VerifyLinks>, budget: usize) -> Result<Self> {
+        let not_found = match mode {
+            Some(_) => {
+                NotFoundRepository::new(ConnectionPool::get_pool()?)?.get_permanent(PERMANENT_FAILURES, RETRY_DAYS)?
+            }
+            None => HashSet::new(),
+        };
+        Ok(Self::with_head(mode, budget, not_found, |url| HTTP.head(url)))
+    }
+
+    pub(super) fn with_head(
+        mode: Option<VerifyLinks>,
+        budget: usize,
+        not_found: HashSet<String>,
+        head: fn(&str) -> Result<u16>,
+    ) -> Self {
+        LinkVerifier {
+            mode,
+            budget,
+            requests: AtomicUsize::new(0),
+            unchecked: AtomicUsize::new(0),
+            not_found,
+            checked: Mutex::new(HashMap::new()),
+            head,
+        }
+    }
+
+    /// Returns the row of a record, None if it is dropped because its URL is dead
+    pub fn verify(&self, item: &JvmData, mut row: Map<String, Value>) -> Option<Map<String, Value>> {
+        let Some(mode) = self.mode else {
+            return Some(row);
+        };
+        if !self.is_dead(&item.url) {
+            return Some(row);
+        }
+        match mode {
+            VerifyLinks::Drop => {
+                warn!("dropping {} from the export, the URL is dead", item.url);
+                None
+            }
+            VerifyLinks::Flag => {
+                row.insert("dead_link".to_string(), Value::Bool(true));
+                Some(row)
+            }
+        }
+    }
+
+    fn is_dead(&self, url: &str) -> bool {
+        if self.not_found.contains(url) {
+            return true;
+        }
+        if let Some(dead) = self.checked.lock().unwrap().get(url) {
+            return *dead;
+        }
+        if self.requests.fetch_add(1, Ordering::SeqCst) >= self.budget {
+            self.unchecked.fetch_add(1, Ordering::SeqCst);
+            return false;
+        }
+        // only missing resources are dead, transient failures must not unpublish records
+        let dead = match (self.head)(url) {
+            Ok(status) => status == 404 || status == 410,
+            Err(err) => {
+                warn!("unable to verify {}: {}", url, err);
+                false
+            }
+        };
+        self.checked.lock().unwrap().insert(url.to_string(), dead);
+        dead
+    }
+
+    /// Logs the number of verified, dead and unchecked URLs
+    pub fn log_summary(&self) {
+        if self.mode.is_none() {
+            return;
+        }
+        let checked = self.checked.lock().unwrap();
+        info!(
+            "verified {} links, {} dead",
+            checked.len(),
+            checked.values().filter(|dead| **dead).count()
+        );
+        let unchecked = self.unchecked.load(Ordering::SeqCst);
+        if unchecked > 0 {
+            warn!(
+                "link budget of {} requests exhausted, {} links exported unchecked",
+                self.budget, unchecked
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(url: &str) -> Result<u16> {
+        match url {
+            "https://example.com/gone.zip" => Ok(410),
+            "https://example.com/missing.zip" => Ok(404),
+            "https://example.com/timeout.zip" => Err(eyre::eyre!("timed out")),
+            _ => Ok(200),
+        }
+    }
+
+    fn get_row(url: &str) -> (JvmData, Map<String, Value>) {
+        let item = JvmData {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let row = JvmData::map(&item, &["url".to_string()], &[]);
+        (item, row)
+    }
+
+    #[test]
+    fn test_verify() {
+        let not_found = HashSet::from(["https://example.com/known.zip".to_string()]);
+        let drop = LinkVerifier::with_head(Some(VerifyLinks::Drop), 10, not_found.clone(), head);
+        let flag = LinkVerifier::with_head(Some(VerifyLinks::Flag), 10, not_found, head);
+        for (url, dead) in [
+            ("https://example.com/ok.zip", false),
+            ("https://example.com/gone.zip", true),
+            ("https://example.com/missing.zip", true),
+            ("https://example.com/known.zip", true),
+            ("https://example.com/timeout.zip", false),
+        ] {
+            let (item, row) = get_row(url);
+            assert_eq!(drop.verify(&item, row.clone()).is_none(), dead, "{}", url);
+            let flagged = flag.verify(&item, row).unwrap();
+            assert_eq!(flagged.get("dead_link") == Some(&Value::Bool(true)), dead, "{}", url);
+        }
+        // the known URL is not requested
+        assert_eq!(drop.requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_verify_budget() {
+        let verifier = LinkVerifier::with_head(Some(VerifyLinks::Drop), 1, HashSet::new(), head);
+        let (item, row) = get_row("https://example.com/missing.zip");
+        assert!(verifier.verify(&item, row.clone()).is_none());
+        // cached, does not count against the budget
+        assert!(verifier.verify(&item, row).is_none());
+
+        let (item, row) = get_row("https://example.com/gone.zip");
+        assert!(verifier.verify(&item, row).is_some());
+        assert_eq!(verifier.unchecked.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_verify_disabled() {
+        let verifier = LinkVerifier::with_head(None, 0, HashSet::new(), |_| panic!("no request expected"));
+        let (item, row) = get_row("https://example.com/missing.zip");
+        assert_eq!(verifier.verify(&item, row.clone()), Some(row));
+    }
+}
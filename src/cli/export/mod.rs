@@ -1,21 +1,46 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use clap::Subcommand;
+use log::error;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde_json::{Map, Value};
 
+use crate::{
+    db::jvm_repository::{Page, Sort},
+    jvm::JvmData,
+    report::Report,
+    script::{self, RowScript},
+};
+
+mod build_tools;
+mod compatibility;
+mod links;
 mod release_type;
 mod vendor;
+mod views;
+
+pub(super) use links::{LinkVerifier, VerifyLinks};
+
+/// Exported properties of a record
+type Row = Map<String, Value>;
 
 #[derive(Debug, Subcommand)]
 enum Commands {
+    BuildTools(build_tools::BuildTools),
+    Compatibility(compatibility::Compatibility),
     ReleaseType(release_type::ReleaseType),
     Vendor(vendor::Vendor),
+    Views(views::Views),
 }
 
 impl Commands {
-    pub fn run(self) -> eyre::Result<()> {
+    pub fn run(self, report: &Report) -> eyre::Result<()> {
         match self {
-            Self::ReleaseType(cmd) => cmd.run(),
-            Self::Vendor(cmd) => cmd.run(),
+            Self::BuildTools(cmd) => cmd.run(report),
+            Self::Compatibility(cmd) => cmd.run(report),
+            Self::ReleaseType(cmd) => cmd.run(report),
+            Self::Vendor(cmd) => cmd.run(report),
+            Self::Views(cmd) => cmd.run(report),
         }
     }
 }
@@ -29,11 +54,63 @@ pub struct Export {
 
 impl Export {
     pub fn run(self) -> eyre::Result<()> {
-        self.command.run()
+        let report = Report::new("export");
+        let result = self.command.run(&report);
+        if let Err(err) = &result {
+            report.failure("export", err);
+        }
+        // a failing report must not replace the error of the export
+        if let Err(err) = report.send() {
+            error!("failed to send the export report: {}", err);
+        }
+        result
     }
 }
 
-fn get_filter_map(filters: Vec<String>) -> HashMap<String, Vec<String>> {
+/// Returns the directory to export to, `--output-dir` takes precedence over `export.path`
+///
+/// The directory is created and checked for write access before any data is exported
+pub(super) fn export_path(output_dir: Option<PathBuf>, configured: Option<&str>) -> eyre::Result<PathBuf> {
+    let path = match (output_dir, configured) {
+        (Some(dir), _) => dir,
+        (None, Some(path)) => PathBuf::from(path),
+        (None, None) => return Err(eyre::eyre!("export.path is not configured, use --output-dir")),
+    };
+    std::fs::create_dir_all(&path)
+        .map_err(|e| eyre::eyre!("unable to create export directory {}: {}", path.display(), e))?;
+    let probe = path.join(".roast-write-test");
+    std::fs::write(&probe, "")
+        .and_then(|_| std::fs::remove_file(&probe))
+        .map_err(|e| eyre::eyre!("export directory {} is not writable: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Order and page of the records of every exported file
+#[derive(Debug, Default, clap::Args)]
+pub(super) struct Paging {
+    /// Sort the records of every file by a column in form of column:asc or column:desc e.g.: version:desc
+    #[clap(long, value_name = "SORT")]
+    pub sort: Vec<Sort>,
+    /// Maximum number of records of every file
+    #[clap(long, value_name = "COUNT")]
+    pub limit: Option<i64>,
+    /// Number of records of every file to skip
+    #[clap(long, value_name = "COUNT", default_value = "0")]
+    pub offset: i64,
+}
+
+impl Paging {
+    pub fn page(self) -> Page {
+        Page {
+            sort: self.sort,
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
+
+/// Parses filters in form of key=value1,value2
+pub(super) fn get_filter_map(filters: Vec<String>) -> HashMap<String, Vec<String>> {
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
     for filter in filters {
         let parts: Vec<&str> = filter.split('=').collect();
@@ -46,3 +123,163 @@ fn get_filter_map(filters: Vec<String>) -> HashMap<String, Vec<String>> {
     }
     map
 }
+
+/// Parses the filters of an export
+///
+/// Only artifacts of the `jvm` component are exported unless the component is filtered explicitly
+pub(super) fn get_export_filter_map(filters: Vec<String>) -> HashMap<String, Vec<String>> {
+    let mut map = get_filter_map(filters);
+    map.entry("component".to_string())
+        .or_insert_with(|| vec!["jvm".to_string()]);
+    map
+}
+
+/// Returns the records and the rows of an exported file
+///
+/// `audit exports` re-creates the files by this function as well, so filters, dropped links and the row script apply
+/// to the audit exactly as to the export. Records whose row was dropped by the script are dropped from both. The page
+/// applies to the records matching the filters, before links are verified.
+pub(super) fn export_rows(
+    data: Vec<JvmData>,
+    filters: &HashMap<String, Vec<String>>,
+    page: &Page,
+    include: &[String],
+    exclude: &[String],
+    verifier: &LinkVerifier,
+    script: &Option<RowScript>,
+) -> eyre::Result<(Vec<JvmData>, Vec<Row>)> {
+    let data = page.apply(data.into_iter().filter(|item| JvmData::filter(item, filters)).collect());
+    let (items, rows) = data
+        .into_par_iter()
+        .filter_map(|item| {
+            let row = verifier.verify(&item, JvmData::map(&item, include, exclude))?;
+            script::transform_row(script, row)
+                .map(|row| row.map(|row| (item, row)))
+                .transpose()
+        })
+        .collect::<eyre::Result<Vec<(JvmData, Row)>>>()?
+        .into_iter()
+        .unzip();
+    Ok((items, rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_export_path() {
+        let dir = std::env::temp_dir().join(format!("roast-export-path-{}", std::process::id()));
+        let output_dir = dir.join("adhoc");
+
+        let path = export_path(Some(output_dir.clone()), Some("public/api/jvm/")).unwrap();
+        assert_eq!(path, output_dir);
+        assert!(output_dir.is_dir());
+        assert!(!output_dir.join(".roast-write-test").exists());
+
+        let configured = dir.join("configured");
+        let path = export_path(None, configured.to_str()).unwrap();
+        assert_eq!(path, configured);
+
+        assert!(export_path(None, None).is_err());
+        std::fs::write(dir.join("file"), "").unwrap();
+        assert!(export_path(Some(dir.join("file")), None).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_filter_map() {
+        let map = get_filter_map(vec!["file_type=tar.gz,zip".to_string(), "features=!musl".to_string()]);
+        assert_eq!(
+            map.get("file_type").unwrap(),
+            &vec!["tar.gz".to_string(), "zip".to_string()]
+        );
+        assert_eq!(map.get("features").unwrap(), &vec!["!musl".to_string()]);
+        assert!(!map.contains_key("component"));
+    }
+
+    #[test]
+    fn test_get_export_filter_map() {
+        let map = get_export_filter_map(vec!["file_type=zip".to_string()]);
+        assert_eq!(map.get("component").unwrap(), &vec!["jvm".to_string()]);
+
+        let map = get_export_filter_map(vec!["component=javafx".to_string()]);
+        assert_eq!(map.get("component").unwrap(), &vec!["javafx".to_string()]);
+    }
+
+    #[test]
+    fn test_export_rows() {
+        let data = ["linux", "windows", "linux"]
+            .iter()
+            .enumerate()
+            .map(|(i, os)| JvmData {
+                component: "jvm".to_string(),
+                os: os.to_string(),
+                url: format!("https://example.com/{}.zip", i),
+                ..Default::default()
+            })
+            .collect::<Vec<JvmData>>();
+        let filters = get_filter_map(vec!["os=linux".to_string()]);
+        let not_found = HashSet::from(["https://example.com/2.zip".to_string()]);
+        let verifier = LinkVerifier::with_head(Some(VerifyLinks::Drop), 0, not_found, |_| Ok(200));
+
+        let (items, rows) = export_rows(
+            data,
+            &filters,
+            &Page::default(),
+            &["url".to_string()],
+            &[],
+            &verifier,
+            &None,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            rows,
+            vec![Map::from_iter([(
+                "url".to_string(),
+                Value::from("https://example.com/0.zip")
+            )])]
+        );
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn test_export_rows_script() {
+        let data = ["tar.gz", "zip"]
+            .iter()
+            .map(|ext| JvmData {
+                url: format!("https://example.com/jdk.{}", ext),
+                ..Default::default()
+            })
+            .collect::<Vec<JvmData>>();
+        let verifier = LinkVerifier::with_head(None, 0, HashSet::new(), |_| Ok(200));
+        let script = RowScript::compile(indoc::indoc! {r#"
+            fn transform(row) {
+                if row.url.ends_with(".zip") {
+                    return ();
+                }
+                row
+            }
+        "#})
+        .unwrap();
+
+        let (items, rows) = export_rows(
+            data,
+            &HashMap::new(),
+            &Page::default(),
+            &["url".to_string()],
+            &[],
+            &verifier,
+            &Some(script),
+        )
+        .unwrap();
+        assert_eq!(
+            items.iter().map(|item| item.url.as_str()).collect::<Vec<_>>(),
+            vec!["https://example.com/jdk.tar.gz"]
+        );
+        assert_eq!(rows.len(), 1);
+    }
+}
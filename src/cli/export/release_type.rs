@@ -1,17 +1,17 @@
-use std::{fs::File, path::PathBuf};
+use std::path::PathBuf;
 
 use eyre::Result;
 use log::info;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use serde_json::{Map, Value};
 
 use crate::{
     config::Conf,
-    db::{jvm_repository::JvmRepository, pool::ConnectionPool},
-    jvm::JvmData,
+    db::{merged_jvm_repository::MergedJvmRepository, pool::ConnectionPool},
+    output::Manifest,
+    report::Report,
+    script::RowScript,
 };
 
-use super::get_filter_map;
+use super::{LinkVerifier, Paging, VerifyLinks, export_path, export_rows, get_export_filter_map};
 
 /// Export by {release_type}/{os}/{architecture}
 ///
@@ -46,16 +46,25 @@ pub struct ReleaseType {
     /// Pretty print JSON
     #[clap(long, default_value = "false")]
     pub pretty: bool,
+    /// Directory to export to, overrides export.path
+    #[clap(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+    /// Verify the download URLs by HEAD requests and drop or flag the records of dead URLs
+    #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "drop")]
+    pub verify_links: Option<VerifyLinks>,
+    /// Maximum number of HEAD requests of --verify-links, remaining URLs are exported unchecked
+    #[clap(long, value_name = "COUNT", default_value = "1000")]
+    pub link_budget: usize,
+    #[clap(flatten)]
+    pub paging: Paging,
 }
 
 impl ReleaseType {
-    pub fn run(self) -> Result<()> {
+    pub fn run(self, report: &Report) -> Result<()> {
         let conf = Conf::try_get()?;
-        if conf.export.path.is_none() {
-            return Err(eyre::eyre!("export.path is not configured"));
-        }
-        let conn_pool = ConnectionPool::get_pool()?;
-        let db = JvmRepository::new(conn_pool)?;
+        let export_path = export_path(self.output_dir, conf.export.path.as_deref())?;
+        let mut manifest = Manifest::load(&export_path)?;
+        let db = MergedJvmRepository::new(ConnectionPool::get_export_pools()?)?;
 
         let release_types_default = db.get_distinct("release_type")?;
         let release_types = self.release_type.unwrap_or(release_types_default);
@@ -69,39 +78,33 @@ impl ReleaseType {
         let include = self.include.unwrap_or_default();
         let exclude = self.exclude.unwrap_or_default();
 
-        let filters = get_filter_map(self.filters.unwrap_or_default());
+        let filters = get_export_filter_map(self.filters.unwrap_or_default());
 
-        let export_path = conf.export.path.unwrap();
+        let script = RowScript::load(&conf)?;
+        let verifier = LinkVerifier::new(self.verify_links, self.link_budget)?;
+        let page = self.paging.page();
 
         for release_type in &release_types {
             for os in &oses {
                 for arch in &archs {
                     let data = db.export_release_type(release_type, arch, os)?;
 
-                    let export_data = data
-                        .into_par_iter()
-                        .filter(|item| JvmData::filter(item, &filters))
-                        .map(|item| JvmData::map(&item, &include, &exclude))
-                        .collect::<Vec<Map<String, Value>>>();
+                    let (_, export_data) = export_rows(data, &filters, &page, &include, &exclude, &verifier, &script)?;
                     let size = export_data.len();
 
                     info!("exporting {} records to {}/{}/{}.json", size, release_type, os, arch);
-                    let path = PathBuf::from(&export_path)
-                        .join(release_type)
-                        .join(os)
-                        .join(format!("{}.json", arch));
+                    let path = export_path.join(release_type).join(os).join(format!("{}.json", arch));
                     if let Some(parent) = path.parent() {
                         std::fs::create_dir_all(parent)?;
                     }
 
-                    let file = File::create(path)?;
-                    match self.pretty {
-                        true => serde_json::to_writer_pretty(file, &export_data)?,
-                        false => serde_json::to_writer(file, &export_data)?,
-                    }
+                    manifest.write_json_file(&path, &export_data, self.pretty, conf.export.brotli)?;
+                    report.success(&format!("{}/{}/{}.json", release_type, os, arch), size as u64);
                 }
             }
         }
+        verifier.log_summary();
+        manifest.save()?;
         Ok(())
     }
 }
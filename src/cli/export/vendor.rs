@@ -1,28 +1,36 @@
-use std::{fs::File, path::PathBuf};
+use std::path::PathBuf;
 
 use eyre::Result;
 use log::info;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use serde_json::{Map, Value};
 
 use crate::{
     config::Conf,
-    db::{jvm_repository::JvmRepository, pool::ConnectionPool},
-    jvm::JvmData,
+    db::{merged_jvm_repository::MergedJvmRepository, pool::ConnectionPool},
+    jvm::{JvmData, alias},
+    output::Manifest,
+    report::Report,
+    script::RowScript,
 };
 
-use super::get_filter_map;
+use super::{LinkVerifier, Paging, Row, VerifyLinks, export_path, export_rows, get_export_filter_map};
 
 /// Export by {vendor}/{os}/{architecture}
 ///
 /// Will export JSON files in form of {vendor}/{os}/{arch}.json to the path specified in the configuration file
-/// or ROAST_EXPORT_PATH environment variable
+/// or ROAST_EXPORT_PATH environment variable. With --major only the releases of a single major version are
+/// queried and exported in form of {vendor}/{major}/{os}/{arch}.json
+///
+/// Aliases of the exported versions e.g. latest, lts, 21, 21-lts, 21.0 are exported to {vendor}/aliases.json, resolved
+/// to the newest GA version of the exported rows they match. Aliases are not exported with --major.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment)]
 pub struct Vendor {
     /// Vendors e.g.: corretto, oracle, zulu
-    #[clap(short = 'v', long, num_args = 0.., value_delimiter = ',', value_name = "VENDOR")]
+    #[clap(short = 'v', long, alias = "vendor", num_args = 0.., value_delimiter = ',', value_name = "VENDOR")]
     pub vendors: Option<Vec<String>>,
+    /// Major Java version e.g.: 8, 17, 21
+    #[clap(short = 'm', long, value_name = "MAJOR")]
+    pub major: Option<u32>,
     /// Operating systems e.g.: linux, macosx, windows
     #[clap(short = 'o', long, num_args = 0.., value_delimiter = ',', value_name = "OS")]
     pub os: Option<Vec<String>>,
@@ -41,16 +49,25 @@ pub struct Vendor {
     /// Pretty print JSON
     #[clap(long, default_value = "false")]
     pub pretty: bool,
+    /// Directory to export to, overrides export.path
+    #[clap(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+    /// Verify the download URLs by HEAD requests and drop or flag the records of dead URLs
+    #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "drop")]
+    pub verify_links: Option<VerifyLinks>,
+    /// Maximum number of HEAD requests of --verify-links, remaining URLs are exported unchecked
+    #[clap(long, value_name = "COUNT", default_value = "1000")]
+    pub link_budget: usize,
+    #[clap(flatten)]
+    pub paging: Paging,
 }
 
 impl Vendor {
-    pub fn run(self) -> Result<()> {
+    pub fn run(self, report: &Report) -> Result<()> {
         let conf = Conf::try_get()?;
-        if conf.export.path.is_none() {
-            return Err(eyre::eyre!("export.path is not configured"));
-        }
-        let conn_pool = ConnectionPool::get_pool()?;
-        let db = JvmRepository::new(conn_pool)?;
+        let export_path = export_path(self.output_dir, conf.export.path.as_deref())?;
+        let mut manifest = Manifest::load(&export_path)?;
+        let db = MergedJvmRepository::new(ConnectionPool::get_export_pools()?)?;
 
         let vendors_default = db.get_distinct("vendor")?;
         let vendors = self.vendors.unwrap_or(vendors_default);
@@ -64,39 +81,101 @@ impl Vendor {
         let include = self.include.unwrap_or_default();
         let exclude = self.exclude.unwrap_or_default();
 
-        let filters = get_filter_map(self.filters.unwrap_or_default());
+        let filters = get_export_filter_map(self.filters.unwrap_or_default());
 
-        let export_path = conf.export.path.unwrap();
+        let script = RowScript::load(&conf)?;
+        let verifier = LinkVerifier::new(self.verify_links, self.link_budget)?;
+        let page = self.paging.page();
 
         for vendor in &vendors {
+            // exported records of the vendor, resolving the aliases of its versions
+            let mut exported = Vec::new();
             for os in &oses {
                 for arch in &archs {
-                    let data = db.export_vendor(vendor, os, arch)?;
+                    let data = match self.major {
+                        Some(major) => db.export_vendor_major(vendor, major as i32, os, arch)?,
+                        None => db.export_vendor(vendor, os, arch)?,
+                    };
 
-                    let export_data = data
-                        .into_par_iter()
-                        .filter(|item| JvmData::filter(item, &filters))
-                        .map(|item| JvmData::map(&item, &include, &exclude))
-                        .collect::<Vec<Map<String, Value>>>();
+                    let (items, export_data) =
+                        export_rows(data, &filters, &page, &include, &exclude, &verifier, &script)?;
+                    exported.extend(as_written(items, &export_data));
                     let size = export_data.len();
 
-                    info!("exporting {} records for {}/{}/{}", size, vendor, os, arch);
-                    let path = PathBuf::from(&export_path)
-                        .join(vendor)
-                        .join(os)
-                        .join(format!("{}.json", arch));
+                    let name = match self.major {
+                        Some(major) => format!("{}/{}/{}/{}.json", vendor, major, os, arch),
+                        None => format!("{}/{}/{}.json", vendor, os, arch),
+                    };
+                    info!("exporting {} records to {}", size, name);
+                    let path = export_path.join(&name);
                     if let Some(parent) = path.parent() {
                         std::fs::create_dir_all(parent)?;
                     }
 
-                    let file = File::create(path)?;
-                    match self.pretty {
-                        true => serde_json::to_writer_pretty(file, &export_data)?,
-                        false => serde_json::to_writer(file, &export_data)?,
-                    }
+                    manifest.write_json_file(&path, &export_data, self.pretty, conf.export.brotli)?;
+                    report.success(&name, size as u64);
                 }
             }
+            // aliases of a single major version would resolve latest and lts to it
+            if self.major.is_none() {
+                let aliases = alias::aliases(&exported);
+                let name = format!("{}/aliases.json", vendor);
+                info!("exporting {} aliases to {}", aliases.len(), name);
+                std::fs::create_dir_all(export_path.join(vendor))?;
+                manifest.write_json_file(&export_path.join(&name), &aliases, self.pretty, conf.export.brotli)?;
+                report.success(&name, aliases.len() as u64);
+            }
         }
+        verifier.log_summary();
+        manifest.save()?;
         Ok(())
     }
 }
+
+/// Returns the records with the versions and release type of their exported rows, which the script may have changed
+///
+/// The aliases are resolved from these, so they only point at versions which are part of the exported files
+fn as_written(items: Vec<JvmData>, rows: &[Row]) -> impl Iterator<Item = JvmData> {
+    items.into_iter().zip(rows).map(|(mut item, row)| {
+        for (key, value) in [
+            ("java_version", &mut item.java_version),
+            ("release_type", &mut item.release_type),
+            ("version", &mut item.version),
+        ] {
+            if let Some(written) = row.get(key).and_then(|v| v.as_str()) {
+                *value = written.to_string();
+            }
+        }
+        item
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_as_written() {
+        let items = ["21.0.4", "21.0.5"]
+            .iter()
+            .map(|version| JvmData {
+                java_version: version.to_string(),
+                release_type: "ga".to_string(),
+                version: version.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let rows = vec![
+            Row::from_iter([("version".to_string(), Value::from("21.0.4+1"))]),
+            Row::from_iter([("release_type".to_string(), Value::from("ea"))]),
+        ];
+
+        let written = as_written(items, &rows).collect::<Vec<_>>();
+        assert_eq!(written[0].version, "21.0.4+1");
+        assert_eq!(written[0].java_version, "21.0.4");
+        assert_eq!(written[1].release_type, "ea");
+        assert_eq!(alias::aliases(&written).get("latest"), Some(&"21.0.4+1".to_string()));
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use log::info;
+use serde_json::{Map, Value};
+
+use crate::{
+    config::{Conf, ViewConf},
+    db::{merged_jvm_repository::MergedJvmRepository, pool::ConnectionPool},
+    jvm::JvmData,
+    output::Manifest,
+    report::Report,
+    script::{self, RowScript},
+};
+
+use super::{export_path, get_filter_map};
+
+/// Export the views defined in the configuration file
+///
+/// Every view defines its own filters, layout and destination. All views are exported from a single
+/// database query. Will export all views if none are specified.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Views {
+    /// Views to export e.g.: public, approved
+    #[clap(value_name = "VIEW")]
+    pub views: Vec<String>,
+    /// Directory to export the views to as {dir}/{name}, overrides export.path and the paths of the views
+    #[clap(long, value_name = "DIR")]
+    pub output_dir: Option<PathBuf>,
+}
+
+impl Views {
+    pub fn run(self, report: &Report) -> Result<()> {
+        let conf = Conf::try_get()?;
+        let views = conf
+            .export
+            .views
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|view| self.views.is_empty() || self.views.contains(&view.name))
+            .collect::<Vec<ViewConf>>();
+        if views.is_empty() {
+            return Err(eyre::eyre!("no export views configured"));
+        }
+
+        let db = MergedJvmRepository::new(ConnectionPool::get_export_pools()?)?;
+        let data = db.export_all()?;
+        let script = RowScript::load(&conf)?;
+
+        for view in &views {
+            let path = match (&self.output_dir, &view.path) {
+                (Some(dir), _) => export_path(Some(dir.join(&view.name)), None)?,
+                (None, Some(path)) => export_path(Some(PathBuf::from(path)), None)?,
+                (None, None) => export_path(None, conf.export.path.as_deref())?.join(&view.name),
+            };
+            export_view(view, &data, &path, &script, conf.export.brotli, report)?;
+        }
+        Ok(())
+    }
+}
+
+fn export_view(
+    view: &ViewConf,
+    data: &[JvmData],
+    path: &Path,
+    script: &Option<RowScript>,
+    brotli: Option<u32>,
+    report: &Report,
+) -> Result<()> {
+    let layout = view.layout.as_deref().unwrap_or("vendor");
+    let include = view.include.clone().unwrap_or_default();
+    let exclude = view.exclude.clone().unwrap_or_default();
+    let filters = get_filter_map(
+        view.filters
+            .as_deref()
+            .map(|f| f.split('&').map(String::from).collect())
+            .unwrap_or_default(),
+    );
+    let matches = |values: &Option<Vec<String>>, value: &String| values.as_ref().is_none_or(|v| v.contains(value));
+
+    let mut manifest = Manifest::load(path)?;
+    let mut files: BTreeMap<(String, String, String), Vec<Map<String, Value>>> = BTreeMap::new();
+    for item in data {
+        if !matches(&view.vendors, &item.vendor)
+            || !matches(&view.os, &item.os)
+            || !matches(&view.arch, &item.architecture)
+            || !JvmData::filter(item, &filters)
+        {
+            continue;
+        }
+        let os = view
+            .os_names
+            .as_ref()
+            .and_then(|names| names.get(&item.os))
+            .unwrap_or(&item.os);
+        let mut row = JvmData::map(item, &include, &exclude);
+        if row.contains_key("os") {
+            row.insert("os".to_string(), Value::String(os.clone()));
+        }
+        let key = match layout {
+            "release_type" => item.release_type.clone(),
+            "vendor" => item.vendor.clone(),
+            _ => return Err(eyre::eyre!("unsupported layout for view {}: {}", view.name, layout)),
+        };
+        files
+            .entry((key, os.clone(), item.architecture.clone()))
+            .or_default()
+            .push(row);
+    }
+
+    for ((key, os, arch), export_data) in files {
+        let export_data = script::transform_rows(script, export_data)?;
+        let size = export_data.len();
+        info!(
+            "[{}] exporting {} records to {}/{}/{}.json",
+            view.name, size, key, os, arch
+        );
+        let file_path = path.join(&key).join(&os).join(format!("{}.json", arch));
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        manifest.write_json_file(&file_path, &export_data, view.pretty, brotli)?;
+        report.success(&format!("{}/{}/{}/{}.json", view.name, key, os, arch), size as u64);
+    }
+    manifest.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs::File};
+
+    use super::*;
+
+    fn get_jvmdata(vendor: &str, os: &str) -> JvmData {
+        JvmData {
+            architecture: "x86_64".to_string(),
+            component: "jvm".to_string(),
+            file_type: "tar.gz".to_string(),
+            os: os.to_string(),
+            release_type: "ga".to_string(),
+            url: format!("https://example.com/{}-{}.tar.gz", vendor, os),
+            vendor: vendor.to_string(),
+            version: "21.0.2".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_export_view() {
+        let path = std::env::temp_dir().join(format!("roast-views-{}", std::process::id()));
+        let data = vec![
+            get_jvmdata("temurin", "linux"),
+            get_jvmdata("temurin", "windows"),
+            get_jvmdata("zulu", "linux"),
+        ];
+        let view = ViewConf {
+            name: "approved".to_string(),
+            vendors: Some(vec!["temurin".to_string()]),
+            include: Some(vec!["url".to_string(), "version".to_string()]),
+            filters: Some("file_type=tar.gz".to_string()),
+            ..Default::default()
+        };
+        let report = Report::new("export");
+
+        export_view(&view, &data, &path, &None, None, &report).unwrap();
+
+        assert!(path.join("temurin/linux/x86_64.json").exists());
+        assert!(path.join("temurin/windows/x86_64.json").exists());
+        assert!(!path.join("zulu").exists());
+        let exported: Vec<Map<String, Value>> =
+            serde_json::from_reader(File::open(path.join("temurin/linux/x86_64.json")).unwrap()).unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].len(), 2);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_export_view_os_names() {
+        let path = std::env::temp_dir().join(format!("roast-views-os-{}", std::process::id()));
+        let data = vec![get_jvmdata("temurin", "macosx"), get_jvmdata("temurin", "linux")];
+        let view = ViewConf {
+            name: "mac".to_string(),
+            os_names: Some(HashMap::from([("macosx".to_string(), "macos".to_string())])),
+            ..Default::default()
+        };
+        let report = Report::new("export");
+
+        export_view(&view, &data, &path, &None, None, &report).unwrap();
+
+        assert!(path.join("temurin/macos/x86_64.json").exists());
+        assert!(path.join("temurin/linux/x86_64.json").exists());
+        assert!(!path.join("temurin/macosx").exists());
+        let exported: Vec<Map<String, Value>> =
+            serde_json::from_reader(File::open(path.join("temurin/macos/x86_64.json")).unwrap()).unwrap();
+        assert_eq!(exported[0].get("os").unwrap(), "macos");
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
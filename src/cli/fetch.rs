@@ -1,11 +1,34 @@
 use crossbeam_channel::{select, unbounded};
 use eyre::Result;
-use log::{error, info};
-use std::{collections::HashMap, sync::Arc};
+use log::{error, info, warn};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
-    db::{jvm_repository::JvmRepository, pool::ConnectionPool},
-    jvm::vendor::{VENDORS, Vendor},
+    build_tool::{BUILD_TOOLS, BuildTool, BuildToolData},
+    cassette::{Cassette, CassetteMode},
+    chaos,
+    db::{
+        asset_repository::AssetRepository,
+        build_tool_repository::BuildToolRepository,
+        fetch_count_repository::FetchCountRepository,
+        jvm_repository::JvmRepository,
+        not_found_repository::{NotFoundRepository, PERMANENT_FAILURES, RETRY_DAYS},
+        pool::ConnectionPool,
+        stats_repository::StatsRepository,
+    },
+    github, http,
+    jvm::{
+        JvmData,
+        vendor::{self, VENDORS, Vendor},
+    },
+    mem,
+    report::Report,
+    stats,
 };
 
 /// Fetch data from JVM vendors
@@ -17,6 +40,24 @@ pub struct Fetch {
     /// Vendors to fetch e.g.: openjdk, zulu
     #[clap(value_name = "VENDOR")]
     pub vendors: Vec<String>,
+    /// Record all HTTP responses to a cassette directory
+    #[clap(long, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Replay HTTP responses from a cassette directory instead of requesting the vendors
+    #[clap(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+    /// Fail the given rate of HTTP requests at random to test the resilience of the vendors e.g.: 0.1
+    #[clap(long, value_name = "RATE", hide = true, num_args = 0..=1, default_missing_value = "0.1")]
+    pub chaos: Option<f64>,
+    /// Fetch one vendor and request at a time in a fixed order for reproducible troubleshooting runs
+    #[clap(long)]
+    pub deterministic: bool,
+    /// Fetch the build tool distributions instead of the JVM vendors e.g.: --build-tools gradle maven
+    #[clap(long)]
+    pub build_tools: bool,
+    /// Log the RSS, peak RSS and allocated memory after every stage
+    #[clap(long)]
+    pub mem_stats: bool,
 }
 
 impl Fetch {
@@ -27,61 +68,103 @@ impl Fetch {
             info!("fetching vendors: {:?}", self.vendors);
         }
 
+        if let Some(path) = &self.record {
+            info!("recording HTTP responses to {}", path.display());
+            Cassette::new(CassetteMode::Record, path).insert()?;
+        }
+        if let Some(path) = &self.replay {
+            info!("replaying HTTP responses from {}", path.display());
+            Cassette::new(CassetteMode::Replay, path).insert()?;
+        }
+
+        if self.mem_stats {
+            mem::enable();
+        }
+        if self.deterministic {
+            info!("deterministic mode enabled, fetching one vendor at a time");
+            rayon::ThreadPoolBuilder::new().num_threads(1).build_global()?;
+        }
+
+        if let Some(rate) = self.chaos {
+            warn!("chaos mode enabled, failing {:.0}% of HTTP requests", rate * 100.0);
+            chaos::enable(rate, self.deterministic)?;
+        }
+
         let start = std::time::Instant::now();
+        let report = Report::new("fetch");
         let conn_pool = ConnectionPool::get_pool()?;
-        let pool = rayon::ThreadPoolBuilder::default().build()?;
-        pool.scope(|s| {
-            let run = |name: String, vendor: Arc<dyn Vendor>| {
-                let conn_pool = conn_pool.clone();
-                s.spawn(move |_| {
-                    let db = match JvmRepository::new(conn_pool) {
-                        Ok(db) => db,
-                        Err(err) => {
-                            error!("[{}] failed to connect to database: {}", name, err);
-                            return;
-                        }
-                    };
-
-                    info!("[{}] fetching meta data", name);
-                    let jvm_data = match vendor.fetch() {
-                        Ok(data) => data,
-                        Err(err) => {
-                            error!("[{}] failed to fetch meta data: {}", name, err);
-                            return;
-                        }
-                    };
-
-                    info!("[{}] writing to database", name);
-                    match db.insert(&jvm_data) {
-                        Ok(result) => {
-                            info!("[{}] inserted/modified {} records", name, result)
-                        }
-                        Err(err) => {
-                            error!("[{}] failed to write to database: {}", name, err);
-                        }
-                    };
-                });
-            };
-
-            let (tx, rx) = unbounded();
-            for (name, vendor) in self.get_vendors() {
-                tx.send((name, vendor)).unwrap();
-            }
-            drop(tx);
-
-            loop {
-                select! {
-                    recv(rx) -> msg => {
-                        match msg {
-                            Ok((name, vendor)) => run(name, vendor),
-                            Err(_) => break,
-                        }
-                    }
+        let threads = if self.deterministic { 1 } else { 0 };
+        let not_found = match self.replay {
+            Some(_) => HashSet::new(),
+            None => NotFoundRepository::new(conn_pool.clone())?.get_permanent(PERMANENT_FAILURES, RETRY_DAYS)?,
+        };
+        if !not_found.is_empty() {
+            info!("skipping {} URLs which permanently returned 404", not_found.len());
+            http::skip_urls(not_found.clone())?;
+        }
+        if self.replay.is_none() && !self.build_tools {
+            match AssetRepository::new(conn_pool.clone())?.get_all() {
+                Ok(checksums) => {
+                    info!("loaded the checksums of {} GitHub assets", checksums.len());
+                    vendor::set_asset_checksums(checksums)?;
                 }
+                Err(err) => warn!("failed to read the checksums of GitHub assets: {}", err),
             }
-        });
+        }
+        let fetched = if self.build_tools {
+            fetch_build_tools(self.get_build_tools(), &report, |data| {
+                let db = BuildToolRepository::new(conn_pool.clone())?;
+                db.insert(data)
+            });
+            BTreeMap::new()
+        } else {
+            fetch_vendors(self.get_vendors(), threads, &report, |_name, jvm_data| {
+                let db = JvmRepository::new(conn_pool.clone())?;
+                db.insert(&without_not_found_checksum_urls(jvm_data, &not_found))
+            })?
+        };
 
+        for repo in github::take_empty_repos() {
+            report.empty(&format!("github:{}", repo), "repository has no releases");
+        }
+        for (host, notice, url) in stats::take_deprecations() {
+            report.deprecated(&format!("http:{}", host), format!("{} (e.g. {})", notice, url));
+        }
         info!("fetched all vendors in {:.2} seconds", start.elapsed().as_secs_f32());
+        mem::stage("fetch");
+        if self.replay.is_none() {
+            let stats = stats::take();
+            if let Err(err) = StatsRepository::new(conn_pool.clone())?.insert(&stats) {
+                warn!("failed to write HTTP statistics of {} hosts: {}", stats.len(), err);
+            }
+            let urls = stats::take_not_found();
+            if let Err(err) = NotFoundRepository::new(conn_pool.clone())?.insert(&urls) {
+                warn!("failed to write {} URLs which returned 404: {}", urls.len(), err);
+            }
+            let checksums = vendor::take_asset_checksums();
+            if let Err(err) = AssetRepository::new(conn_pool.clone())?.insert(&checksums) {
+                warn!(
+                    "failed to write the checksums of {} GitHub assets: {}",
+                    checksums.len(),
+                    err
+                );
+            }
+            if let Err(err) = FetchCountRepository::new(conn_pool)?.insert(&fetched) {
+                warn!(
+                    "failed to write the record counts of {} vendors: {}",
+                    fetched.len(),
+                    err
+                );
+            }
+        }
+        if self.chaos.is_some() {
+            info!(
+                "chaos: injected {} HTTP faults, {} vendor(s) failed",
+                chaos::faults(),
+                report.failures()
+            );
+        }
+        report.send()?;
         Ok(())
     }
 
@@ -92,4 +175,260 @@ impl Fetch {
             .filter(|(k, _v)| self.vendors.is_empty() || self.vendors.contains(k))
             .collect()
     }
+
+    fn get_build_tools(&self) -> Vec<Arc<dyn BuildTool>> {
+        BUILD_TOOLS
+            .iter()
+            .filter(|t| self.vendors.is_empty() || self.vendors.contains(&t.get_name()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Removes the checksum URLs which are known to permanently return 404
+fn without_not_found_checksum_urls(jvm_data: &HashSet<JvmData>, not_found: &HashSet<String>) -> HashSet<JvmData> {
+    jvm_data
+        .iter()
+        .cloned()
+        .map(|mut item| {
+            if item.checksum_url.as_ref().is_some_and(|url| not_found.contains(url)) {
+                item.checksum_url = None;
+            }
+            item
+        })
+        .collect()
+}
+
+/// Fetches the vendors in parallel and passes the data of every vendor to the sink
+///
+/// Vendors are started in order of their name on a pool of the given number of threads, 0 uses the rayon default.
+/// A failing vendor does not affect the others, failures are recorded in the report. Returns the number of records
+/// fetched per vendor, 0 if a vendor returned no records or failed to fetch or write them, so that `guard` flags it.
+fn fetch_vendors<F>(
+    vendors: HashMap<String, Arc<dyn Vendor>>,
+    threads: usize,
+    report: &Report,
+    sink: F,
+) -> Result<BTreeMap<String, u64>>
+where
+    F: Fn(&str, &HashSet<JvmData>) -> Result<u64> + Sync,
+{
+    let fetched = Mutex::new(BTreeMap::new());
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+    pool.scope_fifo(|s| {
+        let sink = &sink;
+        let fetched = &fetched;
+        let run = |name: String, vendor: Arc<dyn Vendor>| {
+            s.spawn_fifo(move |_| {
+                info!("[{}] fetching meta data", name);
+                let jvm_data = match vendor.fetch() {
+                    Ok(data) if data.is_empty() => {
+                        warn!("[{}] no meta data found", name);
+                        report.empty(&name, "no records fetched");
+                        fetched.lock().unwrap().insert(name, 0);
+                        return;
+                    }
+                    Ok(data) => data,
+                    Err(err) => {
+                        error!("[{}] failed to fetch meta data: {}", name, err);
+                        report.failure(&name, format!("failed to fetch meta data: {}", err));
+                        fetched.lock().unwrap().insert(name, 0);
+                        return;
+                    }
+                };
+                mem::stage(&format!("[{}] fetched {} records", name, jvm_data.len()));
+
+                info!("[{}] writing to database", name);
+                match sink(&name, &jvm_data) {
+                    Ok(result) => {
+                        info!("[{}] inserted/modified {} records", name, result);
+                        report.success(&name, result);
+                        fetched.lock().unwrap().insert(name.clone(), jvm_data.len() as u64);
+                    }
+                    Err(err) => {
+                        error!("[{}] failed to write to database: {}", name, err);
+                        report.failure(&name, format!("failed to write to database: {}", err));
+                        fetched.lock().unwrap().insert(name.clone(), 0);
+                    }
+                };
+                drop(jvm_data);
+                mem::stage(&format!("[{}] written", name));
+            });
+        };
+
+        let mut vendors = vendors.into_iter().collect::<Vec<_>>();
+        vendors.sort_by(|a, b| a.0.cmp(&b.0));
+        let (tx, rx) = unbounded();
+        for (name, vendor) in vendors {
+            tx.send((name, vendor)).unwrap();
+        }
+        drop(tx);
+
+        loop {
+            select! {
+                recv(rx) -> msg => {
+                    match msg {
+                        Ok((name, vendor)) => run(name, vendor),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+    });
+    Ok(fetched.into_inner().unwrap())
+}
+
+/// Fetches the build tools in parallel and passes the data of every build tool to the sink
+fn fetch_build_tools<F>(tools: Vec<Arc<dyn BuildTool>>, report: &Report, sink: F)
+where
+    F: Fn(&HashSet<BuildToolData>) -> Result<u64> + Sync,
+{
+    tools.into_par_iter().for_each(|tool| {
+        let name = tool.get_name();
+        info!("[{}] fetching distributions", name);
+        match tool.fetch().and_then(|data| sink(&data)) {
+            Ok(result) => {
+                info!("[{}] inserted/modified {} records", name, result);
+                report.success(&name, result);
+            }
+            Err(err) => {
+                error!("[{}] failed to fetch distributions: {}", name, err);
+                report.failure(&name, format!("failed to fetch distributions: {}", err));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeVendor {
+        name: String,
+        entries: usize,
+        fail: bool,
+    }
+
+    impl Vendor for FakeVendor {
+        fn get_name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn fetch_data(&self, jvm_data: &mut HashSet<JvmData>) -> Result<()> {
+            if self.fail {
+                return Err(eyre::eyre!("chaos: injected Failure for https://example.com"));
+            }
+            for i in 0..self.entries {
+                jvm_data.insert(JvmData {
+                    url: format!("https://example.com/{}/{}", self.name, i),
+                    vendor: self.name.clone(),
+                    ..Default::default()
+                });
+            }
+            Ok(())
+        }
+    }
+
+    fn get_vendors(vendors: &[(&str, usize, bool)]) -> HashMap<String, Arc<dyn Vendor>> {
+        vendors
+            .iter()
+            .map(|(name, entries, fail)| {
+                let vendor: Arc<dyn Vendor> = Arc::new(FakeVendor {
+                    name: name.to_string(),
+                    entries: *entries,
+                    fail: *fail,
+                });
+                (name.to_string(), vendor)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fetch_vendors_isolates_failures() {
+        let vendors = get_vendors(&[("corretto", 2, false), ("temurin", 0, true), ("zulu", 3, false)]);
+        let report = Report::new("fetch");
+        let written = Mutex::new(Vec::new());
+
+        let fetched = fetch_vendors(vendors, 0, &report, |name, jvm_data| {
+            written.lock().unwrap().push(name.to_string());
+            match name {
+                "zulu" => Err(eyre::eyre!("connection reset")),
+                _ => Ok(jvm_data.len() as u64),
+            }
+        })
+        .unwrap();
+
+        let mut written = written.into_inner().unwrap();
+        written.sort();
+        assert_eq!(written, vec!["corretto", "zulu"]);
+        assert_eq!(report.failures(), 2);
+        assert_eq!(report.records(), 2);
+        assert_eq!(
+            fetched,
+            BTreeMap::from([
+                ("corretto".to_string(), 2),
+                ("temurin".to_string(), 0),
+                ("zulu".to_string(), 0)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_fetch_vendors_reports_empty() {
+        let vendors = get_vendors(&[("javafx", 0, false), ("zulu", 1, false)]);
+        let report = Report::new("fetch");
+        let written = Mutex::new(Vec::new());
+
+        let fetched = fetch_vendors(vendors, 0, &report, |name, jvm_data| {
+            written.lock().unwrap().push(name.to_string());
+            Ok(jvm_data.len() as u64)
+        })
+        .unwrap();
+
+        assert_eq!(written.into_inner().unwrap(), vec!["zulu"]);
+        assert_eq!(report.failures(), 0);
+        assert_eq!(report.empties(), 1);
+        assert_eq!(
+            fetched,
+            BTreeMap::from([("javafx".to_string(), 0), ("zulu".to_string(), 1)])
+        );
+    }
+
+    #[test]
+    fn test_without_not_found_checksum_urls() {
+        let get_jvmdata = |url: &str, checksum_url: &str| JvmData {
+            checksum_url: Some(checksum_url.to_string()),
+            url: url.to_string(),
+            ..Default::default()
+        };
+        let jvm_data = HashSet::from([
+            get_jvmdata("https://example.com/a.dmg", "https://example.com/a.dmg.sha256.txt"),
+            get_jvmdata("https://example.com/b.zip", "https://example.com/b.zip.sha256.txt"),
+        ]);
+        let not_found = HashSet::from(["https://example.com/a.dmg.sha256.txt".to_string()]);
+
+        let data = without_not_found_checksum_urls(&jvm_data, &not_found);
+
+        let checksum_url = |url: &str| data.iter().find(|d| d.url == url).unwrap().checksum_url.clone();
+        assert_eq!(checksum_url("https://example.com/a.dmg"), None);
+        assert_eq!(
+            checksum_url("https://example.com/b.zip"),
+            Some("https://example.com/b.zip.sha256.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_fetch_vendors_deterministic() {
+        let vendors = get_vendors(&[("zulu", 1, false), ("corretto", 1, false), ("temurin", 1, false)]);
+        let report = Report::new("fetch");
+        let written = Mutex::new(Vec::new());
+
+        fetch_vendors(vendors, 1, &report, |name, jvm_data| {
+            written.lock().unwrap().push(name.to_string());
+            Ok(jvm_data.len() as u64)
+        })
+        .unwrap();
+
+        assert_eq!(written.into_inner().unwrap(), vec!["corretto", "temurin", "zulu"]);
+    }
 }
@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, fs::File, path::PathBuf};
+
+use eyre::Result;
+use log::{error, info, warn};
+
+use crate::{
+    db::{fetch_count_repository::FetchCountRepository, pool::ConnectionPool},
+    output,
+};
+
+/// Compare the number of records per vendor against a baseline
+///
+/// Compares the number of records every vendor returned on its latest fetch, the database keeps the records of
+/// earlier fetches. Fails if any vendor dropped below the minimum ratio of its baseline count, e.g. because a vendor
+/// page changed and the parser silently stopped matching releases. A vendor which failed on its latest fetch counts 0
+/// records. Run this before publishing exports.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Guard {
+    /// JSON file with the baseline record count per vendor e.g.: {"temurin": 6000}
+    #[clap(long, value_name = "FILE")]
+    pub baseline: PathBuf,
+    /// Minimum ratio of the baseline count every vendor must reach
+    #[clap(long, value_name = "RATIO", default_value = "0.9")]
+    pub min_ratio: f64,
+    /// Write the current record counts to the baseline file instead of comparing them
+    #[clap(long)]
+    pub update: bool,
+}
+
+/// A vendor whose record count dropped below the minimum ratio of its baseline
+#[derive(Debug, PartialEq)]
+struct Regression {
+    vendor: String,
+    baseline: u64,
+    current: u64,
+}
+
+impl Guard {
+    pub fn run(self) -> Result<()> {
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = FetchCountRepository::new(conn_pool)?;
+        let current = db.get_all()?;
+
+        if self.update {
+            let file = File::create(&self.baseline)?;
+            output::write_json(file, &current, true)?;
+            info!(
+                "wrote record counts of {} vendors to {}",
+                current.len(),
+                self.baseline.display()
+            );
+            return Ok(());
+        }
+
+        let file = File::open(&self.baseline)
+            .map_err(|e| eyre::eyre!("unable to open baseline {}: {}", self.baseline.display(), e))?;
+        let baseline: BTreeMap<String, u64> = serde_json::from_reader(file)?;
+        let regressions = regressions(&baseline, &current, self.min_ratio);
+        for vendor in current.keys().filter(|v| !baseline.contains_key(*v)) {
+            warn!("[{}] vendor is not part of the baseline", vendor);
+        }
+        for r in &regressions {
+            error!(
+                "[{}] {} records, expected at least {:.0} ({} in baseline)",
+                r.vendor,
+                r.current,
+                r.baseline as f64 * self.min_ratio,
+                r.baseline
+            );
+        }
+        match regressions.len() {
+            0 => {
+                info!("record counts of {} vendors are within the baseline", baseline.len());
+                Ok(())
+            }
+            n => Err(eyre::eyre!(
+                "{} vendor(s) dropped below {} of the baseline",
+                n,
+                self.min_ratio
+            )),
+        }
+    }
+}
+
+fn regressions(baseline: &BTreeMap<String, u64>, current: &BTreeMap<String, u64>, min_ratio: f64) -> Vec<Regression> {
+    baseline
+        .iter()
+        .filter_map(|(vendor, baseline)| {
+            let current = current.get(vendor).copied().unwrap_or_default();
+            ((current as f64) < *baseline as f64 * min_ratio).then(|| Regression {
+                vendor: vendor.clone(),
+                baseline: *baseline,
+                current,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regressions() {
+        let baseline = BTreeMap::from([
+            ("corretto".to_string(), 1000),
+            ("temurin".to_string(), 1000),
+            ("zulu".to_string(), 1000),
+        ]);
+        let current = BTreeMap::from([
+            ("corretto".to_string(), 900),
+            ("temurin".to_string(), 899),
+            ("microsoft".to_string(), 10),
+        ]);
+
+        assert_eq!(
+            regressions(&baseline, &current, 0.9),
+            vec![
+                Regression {
+                    vendor: "temurin".to_string(),
+                    baseline: 1000,
+                    current: 899,
+                },
+                Regression {
+                    vendor: "zulu".to_string(),
+                    baseline: 1000,
+                    current: 0,
+                },
+            ]
+        );
+        assert!(regressions(&baseline, &current, 0.0).is_empty());
+    }
+}
@@ -2,24 +2,58 @@ use clap::{FromArgMatches, Subcommand};
 use color_eyre::Result;
 use indoc::indoc;
 
+mod audit;
+#[cfg(feature = "tui")]
+mod browse;
+mod confirm;
+mod dev;
 mod export;
 mod fetch;
+mod guard;
+mod plan;
+mod prune;
+mod query;
+mod schema;
+mod smoke;
+mod stats;
 pub mod version;
 
 pub struct Cli {}
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
+    Audit(audit::Audit),
+    #[cfg(feature = "tui")]
+    Browse(browse::Browse),
+    Dev(dev::Dev),
     Fetch(fetch::Fetch),
     Export(export::Export),
+    Guard(guard::Guard),
+    Plan(plan::Plan),
+    Prune(prune::Prune),
+    Query(query::Query),
+    Schema(schema::Schema),
+    Smoke(smoke::Smoke),
+    Stats(stats::Stats),
     Version(version::Version),
 }
 
 impl Commands {
     pub fn run(self) -> Result<()> {
         match self {
+            Self::Audit(cmd) => cmd.run(),
+            #[cfg(feature = "tui")]
+            Self::Browse(cmd) => cmd.run(),
+            Self::Dev(cmd) => cmd.run(),
             Self::Fetch(cmd) => cmd.run(),
             Self::Export(cmd) => cmd.run(),
+            Self::Guard(cmd) => cmd.run(),
+            Self::Plan(cmd) => cmd.run(),
+            Self::Prune(cmd) => cmd.run(),
+            Self::Query(cmd) => cmd.run(),
+            Self::Schema(cmd) => cmd.run(),
+            Self::Smoke(cmd) => cmd.run(),
+            Self::Stats(cmd) => cmd.run(),
             Self::Version(cmd) => cmd.run(),
         }
     }
@@ -33,6 +67,15 @@ impl Cli {
                 .about(env!("CARGO_PKG_DESCRIPTION"))
                 .author("Roland Schär <@roele>")
                 .long_about(LONG_ABOUT)
+                .arg(
+                    clap::Arg::new("error_format")
+                        .long("error-format")
+                        .value_name("FORMAT")
+                        .help("Format of the error printed to stderr if the command fails")
+                        .value_parser(clap::value_parser!(crate::error::ErrorFormat))
+                        .default_value("text")
+                        .global(true),
+                )
                 .arg_required_else_help(true)
                 .subcommand_required(true),
         )
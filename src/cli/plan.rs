@@ -0,0 +1,292 @@
+use eyre::Result;
+use serde::Serialize;
+
+use crate::{
+    config::Conf,
+    db::{jvm_repository::JvmRepository, pool::ConnectionPool},
+    jvm::{
+        JvmData,
+        vendor::{normalize_architecture, normalize_os},
+        version,
+    },
+    output,
+};
+
+/// Compute a download plan for a host
+///
+/// Prints an ordered list of candidates, the latest release of the preferred vendor followed by the latest
+/// releases of the fallback vendors, e.g.:
+///
+///   roast plan --host "$(uname -sm)" --version 21 --prefer vendor=temurin,fallback=zulu
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Plan {
+    /// Host in form of `uname -sm` e.g.: "Linux x86_64", "Darwin arm64"
+    #[clap(long, value_name = "HOST")]
+    pub host: String,
+    /// Java version or version prefix e.g.: 21, 21.0.2
+    #[clap(long, value_name = "VERSION")]
+    pub version: String,
+    /// Vendor preference e.g.: vendor=temurin,fallback=zulu,fallback=corretto. Default: plan.prefer
+    #[clap(long, value_name = "RULES")]
+    pub prefer: Option<String>,
+    /// File types in order of preference e.g.: tar.gz,zip. Default: plan.file_types
+    #[clap(long, num_args = 0.., value_delimiter = ',', value_name = "FILE_TYPE")]
+    pub file_type: Option<Vec<String>>,
+    /// Image type e.g.: jdk, jre
+    #[clap(long, value_name = "IMAGE_TYPE", default_value = "jdk")]
+    pub image_type: String,
+    /// Release type e.g.: ga, ea
+    #[clap(long, value_name = "RELEASE_TYPE", default_value = "ga")]
+    pub release_type: String,
+    /// Features the artifact must have e.g.: musl, javafx. Artifacts with features are skipped otherwise
+    #[clap(long, num_args = 0.., value_delimiter = ',', value_name = "FEATURE")]
+    pub features: Vec<String>,
+    /// Pretty print JSON
+    #[clap(long, default_value = "false")]
+    pub pretty: bool,
+}
+
+/// Ordered vendor preference
+#[derive(Debug, Default, PartialEq)]
+struct Preference {
+    vendors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Candidate {
+    rank: usize,
+    vendor: String,
+    version: String,
+    java_version: String,
+    filename: String,
+    file_type: String,
+    url: String,
+    checksum: Option<String>,
+    size: Option<i32>,
+}
+
+impl Plan {
+    pub fn run(self) -> Result<()> {
+        let conf = Conf::try_get()?;
+        let (os, arch) = parse_host(&self.host)?;
+        let prefer = self
+            .prefer
+            .as_deref()
+            .or(conf.plan.prefer.as_deref())
+            .ok_or_else(|| eyre::eyre!("no vendor preference given, use --prefer or configure plan.prefer"))?;
+        let preference = parse_preference(prefer)?;
+        let file_types = self
+            .file_type
+            .clone()
+            .or(conf.plan.file_types)
+            .unwrap_or_else(|| vec!["tar.gz".to_string(), "zip".to_string()]);
+
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = JvmRepository::new(conn_pool)?;
+        let data = db.export_os(&os, &arch)?;
+
+        let candidates = self
+            .plan(&data, &preference, &file_types)
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| Candidate {
+                rank: i + 1,
+                vendor: item.vendor.clone(),
+                version: item.version.clone(),
+                java_version: item.java_version.clone(),
+                filename: item.filename.clone(),
+                file_type: item.file_type.clone(),
+                url: item.url.clone(),
+                checksum: item.checksum.clone(),
+                size: item.size,
+            })
+            .collect::<Vec<Candidate>>();
+        if candidates.is_empty() {
+            return Err(eyre::eyre!(
+                "no candidates found for {}/{} and version {}",
+                os,
+                arch,
+                self.version
+            ));
+        }
+
+        output::print_json(&candidates, self.pretty)?;
+        Ok(())
+    }
+
+    /// Returns the best release of every preferred vendor in order of preference
+    fn plan<'a>(&self, data: &'a [JvmData], preference: &Preference, file_types: &[String]) -> Vec<&'a JvmData> {
+        let file_type_rank = |item: &JvmData| file_types.iter().position(|f| f == &item.file_type);
+        preference
+            .vendors
+            .iter()
+            .filter_map(|vendor| {
+                data.iter()
+                    .filter(|item| &item.vendor == vendor)
+                    .filter(|item| item.image_type == self.image_type && item.release_type == self.release_type)
+                    .filter(|item| matches_version(&item.java_version, &self.version))
+                    .filter(|item| {
+                        let features = item.features.clone().unwrap_or_default();
+                        self.features.iter().all(|f| features.contains(f))
+                            && features.iter().all(|f| self.features.contains(f))
+                    })
+                    .filter(|item| file_type_rank(item).is_some())
+                    .min_by(|a, b| {
+                        let (va, vb) = (version::parse(&a.java_version), version::parse(&b.java_version));
+                        (vb.major, vb.minor, vb.patch, vb.build)
+                            .cmp(&(va.major, va.minor, va.patch, va.build))
+                            .then_with(|| file_type_rank(a).cmp(&file_type_rank(b)))
+                            .then_with(|| a.url.cmp(&b.url))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Parses a host in form of `uname -sm` into a normalized os and architecture
+fn parse_host(host: &str) -> Result<(String, String)> {
+    let mut parts = host.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(os), Some(arch), None) => Ok((normalize_os(os), normalize_architecture(arch))),
+        _ => Err(eyre::eyre!(
+            "invalid host, expected `uname -sm` output e.g. \"Linux x86_64\": {}",
+            host
+        )),
+    }
+}
+
+/// Parses preference rules in form of vendor=temurin,fallback=zulu,fallback=corretto
+fn parse_preference(rules: &str) -> Result<Preference> {
+    let mut vendor = None;
+    let mut fallbacks = Vec::new();
+    for rule in rules.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        match rule.split_once('=') {
+            Some(("vendor", value)) if vendor.is_none() => vendor = Some(value.to_string()),
+            Some(("vendor", _)) => return Err(eyre::eyre!("only one preferred vendor is allowed: {}", rules)),
+            Some(("fallback", value)) => fallbacks.push(value.to_string()),
+            _ => {
+                return Err(eyre::eyre!(
+                    "invalid preference rule, expected vendor=… or fallback=…: {}",
+                    rule
+                ));
+            }
+        }
+    }
+    let mut vendors = vendor.into_iter().collect::<Vec<String>>();
+    vendors.extend(fallbacks);
+    Ok(Preference { vendors })
+}
+
+/// Returns true if the Java version equals the requested version or starts with it e.g. 21 matches 21.0.2+13
+fn matches_version(java_version: &str, requested: &str) -> bool {
+    java_version == requested
+        || java_version
+            .strip_prefix(requested)
+            .is_some_and(|rest| rest.starts_with(['.', '+', '-']))
+        || version::parse(requested)
+            .major
+            .is_some_and(|major| !requested.contains('.') && version::parse(java_version).major == Some(major))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_jvmdata(vendor: &str, java_version: &str, file_type: &str) -> JvmData {
+        JvmData {
+            architecture: "x86_64".to_string(),
+            file_type: file_type.to_string(),
+            image_type: "jdk".to_string(),
+            java_version: java_version.to_string(),
+            os: "linux".to_string(),
+            release_type: "ga".to_string(),
+            url: format!("https://example.com/{}-{}.{}", vendor, java_version, file_type),
+            vendor: vendor.to_string(),
+            version: java_version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn get_plan(version: &str) -> Plan {
+        Plan {
+            host: "Linux x86_64".to_string(),
+            version: version.to_string(),
+            prefer: None,
+            file_type: None,
+            image_type: "jdk".to_string(),
+            release_type: "ga".to_string(),
+            features: vec![],
+            pretty: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_host() {
+        for (actual, expected) in [
+            ("Linux x86_64", ("linux", "x86_64")),
+            ("Darwin arm64", ("macosx", "aarch64")),
+            ("Linux aarch64", ("linux", "aarch64")),
+        ] {
+            let (os, arch) = parse_host(actual).unwrap();
+            assert_eq!((os.as_str(), arch.as_str()), expected);
+        }
+        assert!(parse_host("Linux").is_err());
+    }
+
+    #[test]
+    fn test_parse_preference() {
+        assert_eq!(
+            parse_preference("vendor=temurin,fallback=zulu,fallback=corretto").unwrap(),
+            Preference {
+                vendors: vec!["temurin".to_string(), "zulu".to_string(), "corretto".to_string()]
+            }
+        );
+        assert!(parse_preference("vendor=temurin,vendor=zulu").is_err());
+        assert!(parse_preference("temurin").is_err());
+    }
+
+    #[test]
+    fn test_matches_version() {
+        for (java_version, requested, expected) in [
+            ("21.0.2+13", "21", true),
+            ("21.0.2+13", "21.0.2", true),
+            ("21.0.2+13", "21.0.1", false),
+            ("21.0.21", "21.0.2", false),
+            ("1.8.0_432", "8", true),
+            ("11.0.25", "1", false),
+        ] {
+            assert_eq!(
+                matches_version(java_version, requested),
+                expected,
+                "{} for {}",
+                java_version,
+                requested
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let data = vec![
+            get_jvmdata("zulu", "21.0.1", "tar.gz"),
+            get_jvmdata("zulu", "21.0.2", "zip"),
+            get_jvmdata("zulu", "21.0.2", "tar.gz"),
+            get_jvmdata("temurin", "21.0.2+13", "tar.gz"),
+            get_jvmdata("temurin", "17.0.10+7", "tar.gz"),
+            get_jvmdata("corretto", "21.0.2.13.1", "tar.gz"),
+        ];
+        let preference = parse_preference("vendor=temurin,fallback=zulu,fallback=liberica").unwrap();
+        let file_types = vec!["tar.gz".to_string(), "zip".to_string()];
+
+        let candidates = get_plan("21").plan(&data, &preference, &file_types);
+
+        assert_eq!(
+            candidates.iter().map(|c| c.url.as_str()).collect::<Vec<&str>>(),
+            vec![
+                "https://example.com/temurin-21.0.2+13.tar.gz",
+                "https://example.com/zulu-21.0.2.tar.gz"
+            ]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use clap::Subcommand;
+use eyre::Result;
+use log::info;
+
+use crate::db::{jvm_repository::JvmRepository, not_found_repository::PERMANENT_FAILURES, pool::ConnectionPool};
+
+use super::confirm::Confirm;
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    ChecksumUrls(ChecksumUrls),
+}
+
+/// Remove stale data
+#[derive(Debug, clap::Args)]
+pub struct Prune {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+impl Prune {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Commands::ChecksumUrls(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Remove checksum URLs which permanently return 404
+///
+/// `fetch` records the URLs which returned 404 and stops requesting them once they failed in multiple fetches. This
+/// removes them from the stored records e.g. the missing dmg/msi checksums of SapMachine.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct ChecksumUrls {
+    /// Number of fetches a checksum URL must have returned 404 in
+    #[clap(long, value_name = "COUNT", default_value_t = PERMANENT_FAILURES)]
+    pub min_failures: i32,
+    #[clap(flatten)]
+    pub confirm: Confirm,
+}
+
+impl ChecksumUrls {
+    pub fn run(self) -> Result<()> {
+        self.confirm.confirm("prune checksum URLs")?;
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = JvmRepository::new(conn_pool)?;
+        let result = db.prune_checksum_urls(self.min_failures)?;
+        info!("removed {} checksum URLs which permanently returned 404", result);
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use eyre::Result;
+
+use crate::{
+    db::{
+        jvm_repository::{JvmRepository, Page, Sort},
+        pool::ConnectionPool,
+    },
+    output,
+};
+
+use super::export::get_filter_map;
+
+/// Query JVM data for spot checks
+///
+/// Prints the records matching the filters as JSON, versions are sorted by their numeric parts
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Query {
+    /// Filters in form of key=value1,value2 or key=!value e.g.: vendor=temurin os=linux
+    #[clap(value_name = "FILTER")]
+    pub filters: Vec<String>,
+    /// Sort by a column in form of column:asc or column:desc e.g.: version:desc
+    #[clap(long, value_name = "SORT")]
+    pub sort: Vec<Sort>,
+    /// Maximum number of records
+    #[clap(long, value_name = "COUNT")]
+    pub limit: Option<i64>,
+    /// Number of records to skip
+    #[clap(long, value_name = "COUNT", default_value = "0")]
+    pub offset: i64,
+    /// Pretty print JSON
+    #[clap(long)]
+    pub pretty: bool,
+}
+
+impl Query {
+    pub fn run(self) -> Result<()> {
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = JvmRepository::new(conn_pool)?;
+        let page = Page {
+            sort: self.sort,
+            limit: self.limit,
+            offset: self.offset,
+        };
+        let data = db.query(&get_filter_map(self.filters), &page)?;
+        output::print_json(&data, self.pretty)
+    }
+}
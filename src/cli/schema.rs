@@ -0,0 +1,280 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use clap::Subcommand;
+use eyre::Result;
+use serde_json::Value;
+
+#[cfg(feature = "scrape-vendors")]
+use crate::jvm::vendor::md_to_html;
+use crate::{
+    db::{jvm_repository::JvmRepository, pool::ConnectionPool},
+    jvm::JvmData,
+    output,
+};
+
+/// Descriptions of the exported properties, every property of [`JvmData`] must be described
+const DESCRIPTIONS: &[(&str, &str)] = &[
+    (
+        "age_days",
+        "Days since fetch last found the record at its vendor, computed at export time, only exported with --include",
+    ),
+    ("architecture", "Normalized architecture e.g. x86_64, aarch64"),
+    ("bitness", "Bitness of the architecture, 32 or 64"),
+    ("build", "Build number of the Java version e.g. 11 for 11.0.25+11"),
+    (
+        "checksum",
+        "Checksum of the artifact in form of algorithm:hex e.g. sha256:...",
+    ),
+    ("checksum_url", "URL to download the checksum"),
+    (
+        "component",
+        "Component of the artifact, only jvm artifacts are exported by default",
+    ),
+    (
+        "distribution_notes",
+        "Notes on the distribution terms of the vendor, configured by export.vendors",
+    ),
+    ("endianness", "Endianness of the architecture, little or big"),
+    ("features", "Features of the artifact e.g. javafx, musl"),
+    ("file_type", "File type of the artifact e.g. tar.gz, zip, msi"),
+    ("filename", "Filename of the artifact"),
+    ("id", "Stable identifier of the artifact, derived from the URL"),
+    ("image_type", "Image type e.g. jdk, jre"),
+    ("installer_kind", "How the artifact is installed e.g. archive, msi, pkg"),
+    ("java_version", "Java version as published by the vendor"),
+    ("jvm_impl", "JVM implementation e.g. hotspot, openj9, graalvm"),
+    ("major", "Major Java version e.g. 8 for 1.8.0_432"),
+    ("minor", "Minor Java version"),
+    (
+        "openjdk_version",
+        "Java version in form of major.minor.patch+build e.g. 8.0.432+6",
+    ),
+    ("os", "Normalized operating system e.g. linux, macosx, windows"),
+    ("patch", "Patch Java version, the update of legacy versions"),
+    (
+        "platform_requirements",
+        "Platform constraints of Linux artifacts e.g. { \"libc\": \"musl\" }",
+    ),
+    ("release_type", "Release type, ga or ea"),
+    (
+        "restricted",
+        "Whether the distribution is restricted e.g. by export-control, configured by export.vendors",
+    ),
+    ("size", "Size of the artifact in bytes"),
+    ("url", "URL to download the artifact"),
+    ("vendor", "Vendor of the artifact"),
+    ("version", "Version of the artifact as published by the vendor"),
+];
+
+/// Properties with a small set of values which are listed as allowed values
+const ENUMERATED: &[&str] = &[
+    "architecture",
+    "bitness",
+    "component",
+    "endianness",
+    "features",
+    "file_type",
+    "image_type",
+    "installer_kind",
+    "jvm_impl",
+    "os",
+    "release_type",
+    "vendor",
+];
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Docs(Docs),
+}
+
+/// Describe the data model
+#[derive(Debug, clap::Args)]
+pub struct Schema {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+impl Schema {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Commands::Docs(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Generate the documentation of the exported properties
+///
+/// Describes every property, its allowed values and which vendors populate it, derived from the records in the
+/// database so the published documentation stays in sync with the code and the data
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Docs {
+    /// Output format
+    #[clap(short = 'f', long, value_name = "FORMAT", default_value = "markdown", value_parser = ["markdown", "html"])]
+    pub format: String,
+    /// Write the documentation to a file instead of stdout
+    #[clap(short = 'o', long, value_name = "FILE")]
+    pub output: Option<std::path::PathBuf>,
+}
+
+/// Documentation of a property
+#[derive(Debug, PartialEq)]
+struct Property {
+    name: String,
+    description: String,
+    values: Vec<String>,
+    /// Number of records populating the property and number of records per vendor
+    coverage: BTreeMap<String, (u64, u64)>,
+}
+
+impl Docs {
+    pub fn run(self) -> Result<()> {
+        let conn_pool = ConnectionPool::get_pool()?;
+        let db = JvmRepository::new(conn_pool)?;
+        let markdown = markdown(&properties(&db.export_all()?)?);
+        let docs = match self.format.as_str() {
+            #[cfg(feature = "scrape-vendors")]
+            "html" => md_to_html(&markdown),
+            #[cfg(not(feature = "scrape-vendors"))]
+            "html" => return Err(eyre::eyre!("HTML output requires the `scrape-vendors` feature")),
+            _ => markdown,
+        };
+        match self.output {
+            Some(path) => std::fs::write(path, docs)?,
+            None => output::print(&docs)?,
+        }
+        Ok(())
+    }
+}
+
+fn properties(data: &[JvmData]) -> Result<Vec<Property>> {
+    let Value::Object(names) = serde_json::to_value(JvmData::default())? else {
+        return Err(eyre::eyre!("unable to list the properties of JvmData"));
+    };
+    let mut properties = Vec::new();
+    for name in names.keys() {
+        let description = DESCRIPTIONS
+            .iter()
+            .find(|(n, _)| *n == name.as_str())
+            .map(|(_, d)| d.to_string())
+            .ok_or_else(|| eyre::eyre!("property {} is not described", name))?;
+        properties.push(Property {
+            name: name.clone(),
+            description,
+            values: Vec::new(),
+            coverage: BTreeMap::new(),
+        });
+    }
+
+    for item in data {
+        let Value::Object(row) = serde_json::to_value(item)? else {
+            continue;
+        };
+        for property in properties.iter_mut() {
+            let value = row.get(&property.name).unwrap_or(&Value::Null);
+            let coverage = property.coverage.entry(item.vendor.clone()).or_default();
+            coverage.1 += 1;
+            if populated(value) {
+                coverage.0 += 1;
+            }
+        }
+    }
+
+    for property in properties.iter_mut().filter(|p| ENUMERATED.contains(&p.name.as_str())) {
+        let mut values = BTreeSet::new();
+        for item in data {
+            match serde_json::to_value(item)?.get(&property.name) {
+                Some(Value::Array(arr)) => values.extend(arr.iter().map(to_string)),
+                Some(value) if populated(value) => {
+                    values.insert(to_string(value));
+                }
+                _ => {}
+            }
+        }
+        property.values = values.into_iter().collect();
+    }
+    Ok(properties)
+}
+
+fn populated(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(arr) => !arr.is_empty(),
+        _ => true,
+    }
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+fn markdown(properties: &[Property]) -> String {
+    let mut md = String::from("# JVM data\n\n| Property | Description | Values | Vendors |\n|---|---|---|---|\n");
+    for property in properties {
+        let vendors = match property.coverage.values().all(|(populated, total)| populated == total) {
+            true if !property.coverage.is_empty() => "all".to_string(),
+            _ => property
+                .coverage
+                .iter()
+                .filter(|(_, (populated, _))| *populated > 0)
+                .map(|(vendor, (populated, total))| format!("{} ({}%)", vendor, populated * 100 / total))
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        md.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            property.name,
+            property.description.replace('|', "\\|"),
+            property.values.join(", "),
+            vendors
+        ));
+    }
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_properties() {
+        let data = vec![
+            JvmData {
+                architecture: "x86_64".to_string(),
+                checksum: Some("sha256:abc".to_string()),
+                features: Some(vec!["musl".to_string()]),
+                url: "https://example.com/a".to_string(),
+                vendor: "temurin".to_string(),
+                ..Default::default()
+            },
+            JvmData {
+                architecture: "aarch64".to_string(),
+                url: "https://example.com/b".to_string(),
+                vendor: "zulu".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let properties = properties(&data).unwrap();
+
+        let get = |name: &str| properties.iter().find(|p| p.name == name).unwrap();
+        assert_eq!(properties.len(), DESCRIPTIONS.len());
+        assert_eq!(get("architecture").values, vec!["aarch64", "x86_64"]);
+        assert_eq!(get("features").values, vec!["musl"]);
+        assert!(get("url").values.is_empty());
+        assert_eq!(
+            get("checksum").coverage,
+            BTreeMap::from([("temurin".to_string(), (1, 1)), ("zulu".to_string(), (0, 1))])
+        );
+
+        let md = markdown(&properties);
+        assert!(md.contains(
+            "| `checksum` | Checksum of the artifact in form of algorithm:hex e.g. sha256:... |  | temurin (100%) |"
+        ));
+        assert!(md.contains("| `url` | URL to download the artifact |  | all |"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use log::{error, info};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    error::VendorError,
+    jvm::{
+        JvmData,
+        vendor::{self, VENDORS},
+    },
+};
+
+/// Fetch a little data of every vendor without writing it to verify the vendors still work
+///
+/// Every vendor only requests the newest of its versions or pages, the first page of paged APIs and the first page
+/// of GitHub releases. Every vendor must return at least one record with all required properties, otherwise the
+/// vendor fails. Exits with an error listing the failing vendors.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Smoke {
+    /// Vendors to check e.g.: openjdk, zulu
+    #[clap(value_name = "VENDOR")]
+    pub vendors: Vec<String>,
+}
+
+impl Smoke {
+    pub fn run(self) -> Result<()> {
+        vendor::minimal_fetch();
+        let vendors = VENDORS
+            .iter()
+            .filter(|v| self.vendors.is_empty() || self.vendors.contains(&v.get_name()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if vendors.is_empty() {
+            return Err(eyre::eyre!("no vendors found: {}", self.vendors.join(", ")));
+        }
+
+        let failures = vendors
+            .into_par_iter()
+            .filter_map(|vendor| {
+                let name = vendor.get_name();
+                let result = vendor
+                    .fetch()
+                    .map_err(|err| err.to_string())
+                    .and_then(|data| check(&data.into_iter().collect::<Vec<_>>()));
+                match result {
+                    Ok(records) => {
+                        info!("[{}] ok, {} valid records", name, records);
+                        None
+                    }
+                    Err(err) => {
+                        error!("[{}] failed: {}", name, err);
+                        Some((name, err))
+                    }
+                }
+            })
+            .collect::<BTreeMap<String, String>>();
+
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(VendorError {
+            vendors: failures.keys().cloned().collect(),
+            message: format!(
+                "smoke test failed for {} vendor(s):\n{}",
+                failures.len(),
+                failures
+                    .iter()
+                    .map(|(name, err)| format!("  {}: {}", name, err))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        }
+        .into())
+    }
+}
+
+/// Returns the number of records with all required properties, fails if there are none
+fn check(data: &[JvmData]) -> Result<usize, String> {
+    if data.is_empty() {
+        return Err("no records fetched".to_string());
+    }
+    let valid = data.iter().filter(|item| missing_properties(item).is_empty()).count();
+    match valid {
+        0 => Err(format!(
+            "none of {} records is valid, e.g. {} misses {}",
+            data.len(),
+            data[0].url,
+            missing_properties(&data[0]).join(", ")
+        )),
+        valid => Ok(valid),
+    }
+}
+
+/// Returns the required properties of a record which are empty
+fn missing_properties(item: &JvmData) -> Vec<&'static str> {
+    [
+        ("architecture", &item.architecture),
+        ("file_type", &item.file_type),
+        ("image_type", &item.image_type),
+        ("java_version", &item.java_version),
+        ("os", &item.os),
+        ("release_type", &item.release_type),
+        ("url", &item.url),
+        ("vendor", &item.vendor),
+        ("version", &item.version),
+    ]
+    .into_iter()
+    .filter(|(_, value)| value.trim().is_empty())
+    .map(|(name, _)| name)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_jvmdata(url: &str, os: &str) -> JvmData {
+        JvmData {
+            architecture: "x86_64".to_string(),
+            file_type: "tar.gz".to_string(),
+            image_type: "jdk".to_string(),
+            java_version: "21.0.5+11".to_string(),
+            os: os.to_string(),
+            release_type: "ga".to_string(),
+            url: url.to_string(),
+            vendor: "temurin".to_string(),
+            version: "21.0.5+11".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(&[]), Err("no records fetched".to_string()));
+        assert_eq!(
+            check(&[
+                get_jvmdata("https://example.com/a.tar.gz", "linux"),
+                get_jvmdata("https://example.com/b.tar.gz", "")
+            ]),
+            Ok(1)
+        );
+        assert_eq!(
+            check(&[get_jvmdata("https://example.com/b.tar.gz", " ")]),
+            Err("none of 1 records is valid, e.g. https://example.com/b.tar.gz misses os".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_properties() {
+        assert!(missing_properties(&get_jvmdata("https://example.com/a.tar.gz", "linux")).is_empty());
+        assert_eq!(missing_properties(&JvmData::default()).len(), 9);
+    }
+}
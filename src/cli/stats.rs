@@ -0,0 +1,72 @@
+use clap::Subcommand;
+use eyre::Result;
+
+use crate::{
+    db::{jvm_repository::JvmRepository, pool::ConnectionPool, stats_repository::StatsRepository},
+    output,
+};
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Vendors(Vendors),
+}
+
+/// Show statistics
+#[derive(Debug, clap::Args)]
+pub struct Stats {
+    #[clap(subcommand)]
+    command: Commands,
+}
+
+impl Stats {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Commands::Vendors(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Show the number of records per vendor
+///
+/// With --network the HTTP latency and error rate per host recorded by `fetch` are shown instead
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub struct Vendors {
+    /// Show HTTP latency and error rate per host
+    #[clap(long)]
+    pub network: bool,
+    /// Number of days to aggregate the network statistics of
+    #[clap(long, value_name = "DAYS", default_value = "7", requires = "network")]
+    pub days: i32,
+}
+
+impl Vendors {
+    pub fn run(self) -> Result<()> {
+        let conn_pool = ConnectionPool::get_pool()?;
+        if !self.network {
+            let db = JvmRepository::new(conn_pool)?;
+            output::println(&format!("{:<20} {:>10}", "VENDOR", "RECORDS"))?;
+            for (vendor, count) in db.count_by_vendor()? {
+                output::println(&format!("{:<20} {:>10}", vendor, count))?;
+            }
+            return Ok(());
+        }
+
+        let db = StatsRepository::new(conn_pool)?;
+        output::println(&format!(
+            "{:<40} {:>10} {:>8} {:>8} {:>8} {:>8}",
+            "HOST", "REQUESTS", "ERRORS", "AVG_MS", "P95_MS", "MAX_MS"
+        ))?;
+        for s in db.get_by_host(self.days)? {
+            let error_rate = match s.requests {
+                0 => 0.0,
+                n => s.errors as f64 * 100.0 / n as f64,
+            };
+            output::println(&format!(
+                "{:<40} {:>10} {:>7.1}% {:>8} {:>8} {:>8}",
+                s.host, s.requests, error_rate, s.avg_ms, s.p95_ms, s.max_ms
+            ))?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::LazyLock;
+use std::{io::Write, sync::LazyLock};
 
 use eyre::Result;
 use versions::Versioning;
@@ -50,6 +50,5 @@ pub fn print_version_if_requested(args: &[String]) -> std::io::Result<()> {
 }
 
 fn show_version() -> std::io::Result<()> {
-    println!("{}", *VERSION);
-    Ok(())
+    writeln!(std::io::stdout(), "{}", *VERSION)
 }
@@ -0,0 +1,137 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use reqwest::Url;
+
+pub use crate::jvm::JvmData;
+
+/// Read-only client of the exported JSON files
+///
+/// Reads the `{release_type|vendor}/{os}/{arch}.json` layout written by `export` from a local directory or an HTTPS
+/// base URL back into [`JvmData`], so consumers don't have to re-implement the parsing of the format e.g.:
+///
+/// ```rust,no_run
+/// use roast::client::{Catalog, filter};
+///
+/// # fn main() -> eyre::Result<()> {
+/// let catalog = Catalog::open("https://example.com/jvm")?;
+/// let jvms = filter(catalog.vendor("temurin", "linux", "x86_64")?, &["file_type=tar.gz", "features=!musl"]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The client is only available if roast is built with the `client` feature, add it as dependency e.g.
+/// `roast = { git = "https://github.com/jdx/mise-java", default-features = false, features = ["client"] }`.
+/// Files are requested by a plain HTTP client, the configuration of roast does not apply.
+#[derive(Clone, Debug)]
+pub struct Catalog {
+    location: Location,
+    http: reqwest::blocking::Client,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Location {
+    Dir(PathBuf),
+    Url(Url),
+}
+
+impl Catalog {
+    /// Opens a catalog, locations starting with `https://` or `http://` are read over HTTP
+    pub fn open(location: &str) -> Result<Self> {
+        let location = match location.starts_with("https://") || location.starts_with("http://") {
+            // the trailing slash ensures relative paths are joined to the base URL instead of replacing its last segment
+            true => Location::Url(Url::parse(&format!("{}/", location.trim_end_matches('/')))?),
+            false => Location::Dir(PathBuf::from(shellexpand::tilde(location).to_string())),
+        };
+        let http = reqwest::blocking::Client::builder()
+            .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Catalog { location, http })
+    }
+
+    /// Reads the records exported by `export release-type`
+    pub fn release_type(&self, release_type: &str, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        self.read(release_type, os, arch)
+    }
+
+    /// Reads the records exported by `export vendor`
+    pub fn vendor(&self, vendor: &str, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        self.read(vendor, os, arch)
+    }
+
+    /// Reads the records of the file `{key}/{os}/{arch}.json`
+    pub fn read(&self, key: &str, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        let file = format!("{}/{}/{}.json", key, os, arch);
+        match &self.location {
+            Location::Dir(path) => read_file(&path.join(&file)),
+            Location::Url(url) => Ok(self.http.get(url.join(&file)?).send()?.error_for_status()?.json()?),
+        }
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<JvmData>> {
+    let file = File::open(path).map_err(|e| eyre::eyre!("unable to open {}: {}", path.display(), e))?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Filters records by filters in form of key=value1,value2 or key=!value, see [`JvmData::filter`]
+pub fn filter(data: Vec<JvmData>, filters: &[&str]) -> Vec<JvmData> {
+    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+    for (key, values) in filters.iter().filter_map(|f| f.split_once('=')) {
+        map.entry(key.to_string())
+            .or_default()
+            .extend(values.split(',').map(String::from));
+    }
+    data.into_iter().filter(|item| JvmData::filter(item, &map)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open() {
+        for (location, expected) in [
+            (
+                "https://example.com/jvm",
+                Location::Url(Url::parse("https://example.com/jvm/").unwrap()),
+            ),
+            (
+                "https://example.com/jvm/",
+                Location::Url(Url::parse("https://example.com/jvm/").unwrap()),
+            ),
+            ("data/", Location::Dir(PathBuf::from("data/"))),
+        ] {
+            assert_eq!(Catalog::open(location).unwrap().location, expected);
+        }
+    }
+
+    #[test]
+    fn test_read_and_filter() {
+        let path = std::env::temp_dir().join(format!("roast-client-{}", std::process::id()));
+        std::fs::create_dir_all(path.join("temurin/linux")).unwrap();
+        std::fs::write(
+            path.join("temurin/linux/x86_64.json"),
+            r#"[
+              {"architecture":"x86_64","features":[],"file_type":"tar.gz","url":"https://example.com/a.tar.gz","vendor":"temurin"},
+              {"architecture":"x86_64","features":["musl"],"file_type":"tar.gz","url":"https://example.com/b.tar.gz","vendor":"temurin"},
+              {"architecture":"x86_64","features":[],"file_type":"zip","url":"https://example.com/c.zip","vendor":"temurin"}
+            ]"#,
+        )
+        .unwrap();
+        let catalog = Catalog::open(path.to_str().unwrap()).unwrap();
+
+        let data = catalog.vendor("temurin", "linux", "x86_64").unwrap();
+        assert_eq!(data.len(), 3);
+        let filtered = filter(data, &["file_type=tar.gz", "features=!musl"]);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].url, "https://example.com/a.tar.gz");
+        assert!(catalog.vendor("zulu", "linux", "x86_64").is_err());
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -1,6 +1,9 @@
 #![allow(unused)]
 
+use std::{collections::HashMap, process::Command};
+
 use confique::{Config, Error};
+use serde::Deserialize;
 use shellexpand::tilde;
 
 #[derive(Config, Debug)]
@@ -8,6 +11,55 @@ pub struct ExportConf {
     /// Path to the export directory
     #[config(env = "ROAST_EXPORT_PATH")]
     pub path: Option<String>,
+    /// Path to a rhai script transforming exported rows, requires the `scripting` feature
+    #[config(env = "ROAST_EXPORT_SCRIPT")]
+    pub script: Option<String>,
+    /// Brotli quality from 0 to 11, writes a compressed {file}.br next to every exported file. Default: disabled
+    #[config(env = "ROAST_EXPORT_BROTLI")]
+    pub brotli: Option<u32>,
+    /// Named export views, each with its own filters, layout and destination
+    pub views: Option<Vec<ViewConf>>,
+    /// URLs of additional databases to export from, rows of the same URL are merged and the newest modified_at wins
+    pub databases: Option<Vec<String>>,
+    /// Vendor specific export settings e.g.: { oracle = { restricted = true } }
+    pub vendors: Option<HashMap<String, ExportVendorConf>>,
+}
+
+/// Export settings of a single vendor
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ExportVendorConf {
+    /// Marks the distributions of the vendor as restricted e.g. by export-control or license constraints
+    pub restricted: Option<bool>,
+    /// Notes on the distribution terms of the vendor, exported as distribution_notes
+    pub distribution_notes: Option<String>,
+}
+
+/// A named, filtered view of the JVM data exported by `export views`
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ViewConf {
+    /// Name of the view
+    pub name: String,
+    /// Directory layout, one of release_type, vendor. Default: vendor
+    pub layout: Option<String>,
+    /// Path to export the view to. Default: {export.path}/{name}
+    pub path: Option<String>,
+    /// Vendors to export e.g.: corretto, temurin
+    pub vendors: Option<Vec<String>>,
+    /// Operating systems to export e.g.: linux, macosx
+    pub os: Option<Vec<String>>,
+    /// Architectures to export e.g.: aarch64, x86_64
+    pub arch: Option<Vec<String>>,
+    /// Properties to include e.g.: url, version
+    pub include: Option<Vec<String>>,
+    /// Properties to exclude e.g.: checksum_url, size
+    pub exclude: Option<Vec<String>>,
+    /// Filters to apply to the data e.g.: file_type=tar.gz,zip&features=!debug
+    pub filters: Option<String>,
+    /// Exported names of operating systems e.g.: { macosx = "macos" }, stored names are used otherwise
+    pub os_names: Option<HashMap<String, String>>,
+    /// Pretty print JSON
+    #[serde(default)]
+    pub pretty: bool,
 }
 
 #[derive(Config, Debug)]
@@ -18,6 +70,12 @@ pub struct DatabaseConf {
     /// Database connection URL
     #[config(env = "ROAST_DATABASE_URL")]
     pub url: Option<String>,
+    /// Path to a file containing the database password, overrides the password of the URL
+    #[config(env = "ROAST_DATABASE_PASSWORD_FILE")]
+    pub password_file: Option<String>,
+    /// Command printing the database password, overrides the password of the URL
+    #[config(env = "ROAST_DATABASE_PASSWORD_COMMAND")]
+    pub password_command: Option<String>,
     /// SSL mode. Default: prefer
     #[config(env = "ROAST_DATABASE_SSL_MODE")]
     pub ssl_mode: Option<String>,
@@ -30,6 +88,91 @@ pub struct DatabaseConf {
     /// SSL Key
     #[config(env = "ROAST_DATABASE_SSL_KEY")]
     pub ssl_key: Option<String>,
+    /// Refuse destructive operations e.g. pruning records, set for production databases. Default: false
+    #[config(env = "ROAST_DATABASE_PROTECTED")]
+    pub protected: Option<bool>,
+}
+
+#[derive(Config, Debug)]
+pub struct ReportConf {
+    /// Comma separated email addresses to send the run report to
+    #[config(env = "ROAST_REPORT_EMAIL_TO")]
+    pub email_to: Option<String>,
+    /// Sender email address. Default: roast@localhost
+    #[config(env = "ROAST_REPORT_EMAIL_FROM")]
+    pub email_from: Option<String>,
+    /// SMTP server host
+    #[config(env = "ROAST_REPORT_SMTP_HOST")]
+    pub smtp_host: Option<String>,
+    /// SMTP server port. Default: 587
+    #[config(env = "ROAST_REPORT_SMTP_PORT")]
+    pub smtp_port: Option<u16>,
+    /// SMTP username
+    #[config(env = "ROAST_REPORT_SMTP_USERNAME")]
+    pub smtp_username: Option<String>,
+    /// SMTP password
+    #[config(env = "ROAST_REPORT_SMTP_PASSWORD")]
+    pub smtp_password: Option<String>,
+    /// Path to a file containing the SMTP password
+    #[config(env = "ROAST_REPORT_SMTP_PASSWORD_FILE")]
+    pub smtp_password_file: Option<String>,
+    /// Command printing the SMTP password
+    #[config(env = "ROAST_REPORT_SMTP_PASSWORD_COMMAND")]
+    pub smtp_password_command: Option<String>,
+}
+
+#[derive(Config, Debug)]
+pub struct GitHubConf {
+    /// GitHub API token
+    #[config(env = "GITHUB_TOKEN")]
+    pub token: Option<String>,
+    /// Path to a file containing the GitHub API token
+    #[config(env = "ROAST_GITHUB_TOKEN_FILE")]
+    pub token_file: Option<String>,
+    /// Command printing the GitHub API token e.g.: pass show roast/github
+    #[config(env = "ROAST_GITHUB_CREDENTIAL_COMMAND")]
+    pub credential_command: Option<String>,
+}
+
+#[derive(Config, Debug)]
+pub struct HttpConf {
+    /// Pinned certificates per host e.g.: "api.adoptium.net" = ["sha256/<base64>"]
+    ///
+    /// Pins are either `sha256/<base64>` of the public key (SubjectPublicKeyInfo) or `cert-sha256/<base64>` of the
+    /// whole certificate. Requests to a pinned host fail unless the server certificate matches one of its pins.
+    #[config(validate = validate_pins)]
+    pub pins: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Config, Debug)]
+pub struct FetchConf {
+    /// Minimum major Java version to fetch, older releases are skipped e.g.: 11
+    #[config(env = "ROAST_FETCH_MIN_MAJOR")]
+    pub min_major: Option<i32>,
+    /// Vendor specific settings overriding the global ones e.g.: { zulu = { min_major = 8 } }
+    pub vendors: Option<HashMap<String, FetchVendorConf>>,
+    /// GitHub repositories to fetch releases from, all by default e.g.: ["corretto/*", "SAP/SapMachine"]
+    pub allow_repos: Option<Vec<String>>,
+    /// GitHub repositories to skip, takes precedence over allow_repos e.g.: ["corretto/corretto-8"]
+    pub deny_repos: Option<Vec<String>>,
+}
+
+/// Fetch settings of a single vendor
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct FetchVendorConf {
+    /// Minimum major Java version to fetch of the vendor
+    pub min_major: Option<i32>,
+    /// Adoptium projects to fetch, only used by temurin e.g.: ["jdk", "jfr"]. Default: ["jdk"]
+    pub projects: Option<Vec<String>>,
+}
+
+#[derive(Config, Debug)]
+pub struct PlanConf {
+    /// Default vendor preference of `plan` e.g.: vendor=temurin,fallback=zulu
+    #[config(env = "ROAST_PLAN_PREFER")]
+    pub prefer: Option<String>,
+    /// File types in order of preference e.g.: ["tar.gz", "zip"]
+    pub file_types: Option<Vec<String>>,
 }
 
 #[derive(Config, Debug)]
@@ -37,7 +180,17 @@ pub struct Conf {
     #[config(nested)]
     pub export: ExportConf,
     #[config(nested)]
+    pub fetch: FetchConf,
+    #[config(nested)]
     pub database: DatabaseConf,
+    #[config(nested)]
+    pub report: ReportConf,
+    #[config(nested)]
+    pub github: GitHubConf,
+    #[config(nested)]
+    pub http: HttpConf,
+    #[config(nested)]
+    pub plan: PlanConf,
 }
 
 impl Conf {
@@ -50,3 +203,243 @@ impl Conf {
         Ok(conf)
     }
 }
+
+/// Fails unless every pin is `sha256/<base64>` or `cert-sha256/<base64>` of a SHA-256 digest
+pub fn validate_pins(pins: &HashMap<String, Vec<String>>) -> Result<(), String> {
+    for (host, host_pins) in pins {
+        if host_pins.is_empty() {
+            return Err(format!("no pins configured for {}", host));
+        }
+        for pin in host_pins {
+            let digest = pin
+                .strip_prefix("sha256/")
+                .or_else(|| pin.strip_prefix("cert-sha256/"))
+                .and_then(|digest| openssl::base64::decode_block(digest).ok());
+            if digest.is_none_or(|digest| digest.len() != 32) {
+                return Err(format!(
+                    "invalid pin for {}: {} (expected sha256/<base64> or cert-sha256/<base64>)",
+                    host, pin
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl DatabaseConf {
+    /// Returns the database password if configured by a file or command
+    pub fn password(&self) -> eyre::Result<Option<String>> {
+        resolve_secret(None, self.password_file.as_deref(), self.password_command.as_deref())
+    }
+}
+
+impl FetchConf {
+    /// Returns the minimum major Java version of a vendor, the vendor setting takes precedence over the global one
+    pub fn min_major(&self, vendor: &str) -> Option<i32> {
+        self.vendors
+            .as_ref()
+            .and_then(|vendors| vendors.get(vendor))
+            .and_then(|v| v.min_major)
+            .or(self.min_major)
+    }
+
+    /// Returns the projects to fetch of a vendor, `jdk` if none are configured
+    pub fn projects(&self, vendor: &str) -> Vec<String> {
+        self.vendors
+            .as_ref()
+            .and_then(|vendors| vendors.get(vendor))
+            .and_then(|v| v.projects.clone())
+            .unwrap_or_else(|| vec!["jdk".to_string()])
+    }
+}
+
+impl ReportConf {
+    /// Returns the SMTP password
+    pub fn smtp_password(&self) -> eyre::Result<Option<String>> {
+        resolve_secret(
+            self.smtp_password.as_deref(),
+            self.smtp_password_file.as_deref(),
+            self.smtp_password_command.as_deref(),
+        )
+    }
+}
+
+impl GitHubConf {
+    /// Returns the GitHub API token
+    pub fn token(&self) -> eyre::Result<Option<String>> {
+        resolve_secret(
+            self.token.as_deref(),
+            self.token_file.as_deref(),
+            self.credential_command.as_deref(),
+        )
+    }
+}
+
+/// Resolves a secret from a plain value, a file or an external command, in that order
+///
+/// Files must not be accessible by group or others (e.g. 0600) and commands are run by the shell, their trimmed
+/// standard output is used as the secret.
+pub fn resolve_secret(value: Option<&str>, file: Option<&str>, command: Option<&str>) -> eyre::Result<Option<String>> {
+    if let Some(value) = value.filter(|v| !v.is_empty()) {
+        return Ok(Some(value.to_string()));
+    }
+    if let Some(file) = file {
+        let path = tilde(file).into_owned();
+        check_permissions(&path)?;
+        let secret =
+            std::fs::read_to_string(&path).map_err(|e| eyre::eyre!("failed to read secret {}: {}", path, e))?;
+        return Ok(Some(secret.trim().to_string()));
+    }
+    if let Some(command) = command {
+        let output = match cfg!(windows) {
+            true => Command::new("cmd").args(["/C", command]).output()?,
+            false => Command::new("sh").args(["-c", command]).output()?,
+        };
+        if !output.status.success() {
+            return Err(eyre::eyre!(
+                "credential command failed with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        return Ok(Some(String::from_utf8(output.stdout)?.trim().to_string()));
+    }
+    Ok(None)
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &str) -> eyre::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(eyre::eyre!(
+            "permissions {:o} of secret {} are too open, expected 0600",
+            mode & 0o777,
+            path
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &str) -> eyre::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_projects() {
+        let conf = FetchConf {
+            min_major: None,
+            vendors: Some(HashMap::from([(
+                "temurin".to_string(),
+                FetchVendorConf {
+                    projects: Some(vec!["jdk".to_string(), "jfr".to_string()]),
+                    ..Default::default()
+                },
+            )])),
+            allow_repos: None,
+            deny_repos: None,
+        };
+        assert_eq!(conf.projects("temurin"), vec!["jdk".to_string(), "jfr".to_string()]);
+        assert_eq!(conf.projects("zulu"), vec!["jdk".to_string()]);
+    }
+
+    #[test]
+    fn test_fetch_min_major() {
+        let conf = FetchConf {
+            min_major: Some(11),
+            vendors: Some(HashMap::from([
+                (
+                    "zulu".to_string(),
+                    FetchVendorConf {
+                        min_major: Some(8),
+                        ..Default::default()
+                    },
+                ),
+                ("temurin".to_string(), FetchVendorConf::default()),
+            ])),
+            allow_repos: None,
+            deny_repos: None,
+        };
+        assert_eq!(conf.min_major("zulu"), Some(8));
+        assert_eq!(conf.min_major("temurin"), Some(11));
+        assert_eq!(conf.min_major("corretto"), Some(11));
+
+        let conf = FetchConf {
+            min_major: None,
+            vendors: None,
+            allow_repos: None,
+            deny_repos: None,
+        };
+        assert_eq!(conf.min_major("zulu"), None);
+    }
+
+    #[test]
+    fn test_validate_pins() {
+        let pin = format!("sha256/{}", openssl::base64::encode_block(&[0; 32]));
+        let cert_pin = format!("cert-sha256/{}", openssl::base64::encode_block(&[0; 32]));
+        let pins = |host_pins: &[&str]| {
+            HashMap::from([(
+                "api.adoptium.net".to_string(),
+                host_pins.iter().map(|p| p.to_string()).collect(),
+            )])
+        };
+        assert!(validate_pins(&pins(&[&pin, &cert_pin])).is_ok());
+        assert!(validate_pins(&HashMap::new()).is_ok());
+        assert!(validate_pins(&pins(&[])).is_err());
+        assert_eq!(
+            validate_pins(&pins(&["sha1/AAAA"])),
+            Err(
+                "invalid pin for api.adoptium.net: sha1/AAAA (expected sha256/<base64> or cert-sha256/<base64>)"
+                    .to_string()
+            )
+        );
+        assert!(validate_pins(&pins(&["sha256/not base64"])).is_err());
+        assert!(validate_pins(&pins(&["sha256/AAAA"])).is_err());
+    }
+
+    #[test]
+    fn test_resolve_secret_value() {
+        assert_eq!(
+            resolve_secret(Some("secret"), Some("/does/not/exist"), None).unwrap(),
+            Some("secret".to_string())
+        );
+        assert_eq!(resolve_secret(None, None, None).unwrap(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_secret_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("roast-secret-{}", std::process::id()));
+        std::fs::write(&path, "secret\n").unwrap();
+        let file = path.to_str().unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(
+            resolve_secret(None, Some(file), None).unwrap(),
+            Some("secret".to_string())
+        );
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(resolve_secret(None, Some(file), None).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_secret_command() {
+        assert_eq!(
+            resolve_secret(None, None, Some("echo secret")).unwrap(),
+            Some("secret".to_string())
+        );
+        assert!(resolve_secret(None, None, Some("exit 1")).is_err());
+    }
+}
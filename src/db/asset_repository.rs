@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use eyre::Result;
+use indoc::indoc;
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+
+use crate::jvm::vendor::AssetChecksum;
+
+pub struct AssetRepository {
+    pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
+}
+
+impl AssetRepository {
+    pub fn new(pool: Pool<PostgresConnectionManager<MakeTlsConnector>>) -> Result<Self> {
+        Ok(AssetRepository { pool })
+    }
+
+    /// Inserts the checksums of assets, the checksums of known assets are replaced if the asset was updated
+    pub fn insert(&self, checksums: &[AssetChecksum]) -> Result<u64> {
+        let mut conn = self.pool.get()?;
+        let mut tx = conn.transaction()?;
+        let stmt = tx.prepare(indoc! {
+          "INSERT INTO ASSET (url, updated_at, checksum) VALUES ($1, $2, $3)
+          ON CONFLICT(url) DO UPDATE SET
+              updated_at = EXCLUDED.updated_at,
+              checksum = EXCLUDED.checksum,
+              modified_at = RFC3339_UTC(CURRENT_TIMESTAMP)
+          WHERE
+              ASSET.updated_at IS DISTINCT FROM EXCLUDED.updated_at
+              OR ASSET.checksum IS DISTINCT FROM EXCLUDED.checksum
+          ;"
+        })?;
+        let mut result = 0;
+        for checksum in checksums {
+            result += tx.execute(&stmt, &[&checksum.url, &checksum.updated_at, &checksum.checksum])?;
+        }
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Returns the checksums of all assets keyed by their URL
+    pub fn get_all(&self) -> Result<HashMap<String, AssetChecksum>> {
+        let mut conn = self.pool.get()?;
+        let stmt = indoc! {
+          "SELECT
+              url,
+              updated_at,
+              checksum
+          FROM
+              ASSET
+          ;",
+        };
+        let rows = conn.query(stmt, &[])?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let checksum = AssetChecksum {
+                    url: row.get("url"),
+                    updated_at: row.get("updated_at"),
+                    checksum: row.get("checksum"),
+                };
+                (checksum.url.clone(), checksum)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use eyre::Result;
+use indoc::indoc;
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+
+use crate::build_tool::BuildToolData;
+
+const BATCH_SIZE: usize = 1000;
+
+pub struct BuildToolRepository {
+    pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
+}
+
+impl BuildToolRepository {
+    pub fn new(pool: Pool<PostgresConnectionManager<MakeTlsConnector>>) -> Result<Self> {
+        Ok(BuildToolRepository { pool })
+    }
+
+    pub fn insert(&self, data: &HashSet<BuildToolData>) -> Result<u64> {
+        let mut conn = self.pool.get()?;
+        let mut result = 0;
+        let mut tx = conn.transaction()?;
+        let columns = 9;
+
+        let mut data = data.iter().collect::<Vec<&BuildToolData>>();
+        data.sort_by(|a, b| a.url.cmp(&b.url));
+        for chunk in data.chunks(BATCH_SIZE) {
+            let mut query = String::from(
+                "INSERT INTO BUILD_TOOL
+                (checksum, checksum_url, file_type, filename, release_type, size, tool, url, version)
+                VALUES ",
+            );
+
+            let mut params: Vec<&(dyn postgres::types::ToSql + Sync)> = Vec::new();
+            for (i, item) in chunk.iter().enumerate() {
+                if i > 0 {
+                    query.push(',');
+                }
+                let placeholders = (1..=columns)
+                    .map(|c| format!("${}", i * columns + c))
+                    .collect::<Vec<String>>();
+                query.push_str(&format!("({})", placeholders.join(", ")));
+                params.push(&item.checksum);
+                params.push(&item.checksum_url);
+                params.push(&item.file_type);
+                params.push(&item.filename);
+                params.push(&item.release_type);
+                params.push(&item.size);
+                params.push(&item.tool);
+                params.push(&item.url);
+                params.push(&item.version);
+            }
+
+            query.push_str(
+                " ON CONFLICT(url) DO UPDATE SET
+                checksum = excluded.checksum,
+                checksum_url = excluded.checksum_url,
+                file_type = excluded.file_type,
+                filename = excluded.filename,
+                modified_at = RFC3339_UTC(CURRENT_TIMESTAMP),
+                release_type = excluded.release_type,
+                size = excluded.size,
+                tool = excluded.tool,
+                version = excluded.version
+                WHERE
+                   excluded.checksum IS DISTINCT FROM BUILD_TOOL.checksum
+                OR excluded.checksum_url IS DISTINCT FROM BUILD_TOOL.checksum_url
+                OR excluded.file_type != BUILD_TOOL.file_type
+                OR excluded.filename != BUILD_TOOL.filename
+                OR excluded.release_type != BUILD_TOOL.release_type
+                OR excluded.size IS DISTINCT FROM BUILD_TOOL.size
+                OR excluded.tool != BUILD_TOOL.tool
+                OR excluded.version != BUILD_TOOL.version
+                ;",
+            );
+
+            result += tx.execute(&query, &params)?;
+        }
+
+        tx.commit()?;
+        Ok(result)
+    }
+
+    pub fn export_tool(&self, tool: &str) -> Result<Vec<BuildToolData>> {
+        let mut conn = self.pool.get()?;
+        let stmt = indoc! {
+          "SELECT
+              checksum,
+              checksum_url,
+              file_type,
+              filename,
+              release_type,
+              size,
+              tool,
+              url,
+              version
+          FROM
+              BUILD_TOOL
+          WHERE
+              tool = $1
+          ORDER BY
+              url ASC
+          ;",
+        };
+        let rows = conn.query(stmt, &[&tool])?;
+        Ok(rows
+            .iter()
+            .map(|row| BuildToolData {
+                checksum: row.get("checksum"),
+                checksum_url: row.get("checksum_url"),
+                file_type: row.get("file_type"),
+                filename: row.get("filename"),
+                release_type: row.get("release_type"),
+                size: row.get("size"),
+                tool: row.get("tool"),
+                url: row.get("url"),
+                version: row.get("version"),
+            })
+            .collect())
+    }
+
+    pub fn get_tools(&self) -> Result<Vec<String>> {
+        let mut conn = self.pool.get()?;
+        let rows = conn.query("SELECT DISTINCT tool FROM BUILD_TOOL ORDER BY tool ASC;", &[])?;
+        Ok(rows.iter().map(|row| row.get::<usize, String>(0)).collect())
+    }
+}
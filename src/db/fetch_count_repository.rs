@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+
+use eyre::Result;
+use indoc::indoc;
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+
+pub struct FetchCountRepository {
+    pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
+}
+
+impl FetchCountRepository {
+    pub fn new(pool: Pool<PostgresConnectionManager<MakeTlsConnector>>) -> Result<Self> {
+        Ok(FetchCountRepository { pool })
+    }
+
+    /// Replaces the number of records fetched per vendor by the counts of the latest fetch
+    pub fn insert(&self, counts: &BTreeMap<String, u64>) -> Result<u64> {
+        let mut conn = self.pool.get()?;
+        let mut tx = conn.transaction()?;
+        let stmt = tx.prepare(indoc! {
+          "INSERT INTO FETCH_COUNT (vendor, records) VALUES ($1, $2)
+          ON CONFLICT(vendor) DO UPDATE SET
+              records = EXCLUDED.records,
+              fetched_at = RFC3339_UTC(CURRENT_TIMESTAMP)
+          ;"
+        })?;
+        let mut result = 0;
+        for (vendor, records) in counts {
+            result += tx.execute(&stmt, &[vendor, &(*records as i64)])?;
+        }
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Returns the number of records every vendor returned on its latest fetch
+    pub fn get_all(&self) -> Result<BTreeMap<String, u64>> {
+        let mut conn = self.pool.get()?;
+        let rows = conn.query("SELECT vendor, records FROM FETCH_COUNT;", &[])?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("vendor"), row.get::<_, i64>("records") as u64))
+            .collect())
+    }
+}
@@ -1,14 +1,168 @@
-use std::collections::HashSet;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
+};
 
-use crate::jvm::JvmData;
+use crate::jvm::{JvmData, arch, distribution, freshness, installer, libc, version};
+use chrono::Utc;
 use eyre::Result;
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 use postgres_openssl::MakeTlsConnector;
 use r2d2::Pool;
 use r2d2_postgres::PostgresConnectionManager;
+use serde_json::Value;
 
 const BATCH_SIZE: usize = 1000;
 
+/// Columns which can be filtered by [`JvmRepository::query`]
+const FILTER_COLUMNS: &[&str] = &[
+    "architecture",
+    "component",
+    "features",
+    "file_type",
+    "image_type",
+    "java_version",
+    "jvm_impl",
+    "os",
+    "release_type",
+    "vendor",
+    "version",
+];
+
+/// Columns which can be sorted by [`JvmRepository::query`]
+const SORT_COLUMNS: &[&str] = &[
+    "architecture",
+    "component",
+    "file_type",
+    "filename",
+    "image_type",
+    "java_version",
+    "jvm_impl",
+    "major",
+    "os",
+    "release_type",
+    "size",
+    "url",
+    "vendor",
+    "version",
+];
+
+/// Order of [`JvmRepository::query`] in form of column:asc or column:desc
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sort {
+    pub column: String,
+    pub descending: bool,
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (column, direction) = s.split_once(':').unwrap_or((s, "asc"));
+        if !SORT_COLUMNS.contains(&column) {
+            return Err(format!(
+                "unable to sort by {}, use one of {}",
+                column,
+                SORT_COLUMNS.join(", ")
+            ));
+        }
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            _ => return Err(format!("unknown sort direction {}, use asc or desc", direction)),
+        };
+        Ok(Sort {
+            column: column.to_string(),
+            descending,
+        })
+    }
+}
+
+impl Sort {
+    /// Returns the ORDER BY expression, versions are sorted by their numeric parts
+    fn to_sql(&self) -> String {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        match self.column.as_str() {
+            "java_version" | "version" => ["major", "minor", "patch", "build"]
+                .iter()
+                .map(|c| format!("{} {} NULLS LAST", c, direction))
+                .chain([format!("{} {}", self.column, direction)])
+                .collect::<Vec<_>>()
+                .join(", "),
+            column => format!("{} {} NULLS LAST", column, direction),
+        }
+    }
+
+    /// Compares records like the ORDER BY expression of [`Sort::to_sql`]
+    fn compare(&self, a: &JvmData, b: &JvmData) -> Ordering {
+        let nulls_last = |a: Option<i32>, b: Option<i32>| match (a, b) {
+            (Some(a), Some(b)) => self.direction(a.cmp(&b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        match self.column.as_str() {
+            "java_version" | "version" => nulls_last(a.major, b.major)
+                .then_with(|| nulls_last(a.minor, b.minor))
+                .then_with(|| nulls_last(a.patch, b.patch))
+                .then_with(|| nulls_last(a.build, b.build))
+                .then_with(|| self.direction(text(a, &self.column).cmp(text(b, &self.column)))),
+            "major" => nulls_last(a.major, b.major),
+            "size" => nulls_last(a.size, b.size),
+            column => self.direction(text(a, column).cmp(text(b, column))),
+        }
+    }
+
+    fn direction(&self, ordering: Ordering) -> Ordering {
+        if self.descending { ordering.reverse() } else { ordering }
+    }
+}
+
+/// Returns the value of a text column of [`SORT_COLUMNS`]
+fn text<'a>(item: &'a JvmData, column: &str) -> &'a str {
+    match column {
+        "architecture" => &item.architecture,
+        "component" => &item.component,
+        "file_type" => &item.file_type,
+        "filename" => &item.filename,
+        "image_type" => &item.image_type,
+        "java_version" => &item.java_version,
+        "jvm_impl" => &item.jvm_impl,
+        "os" => &item.os,
+        "release_type" => &item.release_type,
+        "vendor" => &item.vendor,
+        "version" => &item.version,
+        _ => &item.url,
+    }
+}
+
+/// Order and page of [`JvmRepository::query`]
+#[derive(Clone, Debug, Default)]
+pub struct Page {
+    pub sort: Vec<Sort>,
+    pub limit: Option<i64>,
+    pub offset: i64,
+}
+
+impl Page {
+    /// Sorts and pages records in memory like [`JvmRepository::query`] does in SQL
+    ///
+    /// Exports read the records of several databases, so they can't be paged by the database.
+    pub fn apply(&self, mut data: Vec<JvmData>) -> Vec<JvmData> {
+        data.sort_by(|a, b| {
+            self.sort
+                .iter()
+                .fold(Ordering::Equal, |ordering, sort| {
+                    ordering.then_with(|| sort.compare(a, b))
+                })
+                .then_with(|| a.url.cmp(&b.url))
+        });
+        let limit = self.limit.map_or(usize::MAX, |limit| limit.max(0) as usize);
+        data.into_iter().skip(self.offset.max(0) as usize).take(limit).collect()
+    }
+}
+
 pub struct JvmRepository {
     pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
 }
@@ -22,12 +176,12 @@ impl JvmRepository {
         let mut conn = self.pool.get()?;
         let mut result = 0;
         let mut tx = conn.transaction()?;
-        let columns = 15;
+        let columns = 22;
 
         for chunk in map_workaround(jvm_data).chunks(BATCH_SIZE) {
             let mut query = String::from(
                 "INSERT INTO JVM
-                (architecture, checksum, checksum_url, features, file_type, filename, image_type, java_version, jvm_impl, os, release_type, size, url, vendor, version)
+                (architecture, build, checksum, checksum_url, component, features, file_type, filename, image_type, java_version, jvm_impl, major, minor, openjdk_version, os, patch, platform_requirements, release_type, size, url, vendor, version)
                 VALUES "
             );
 
@@ -36,34 +190,27 @@ impl JvmRepository {
                 if i > 0 {
                     query.push(',');
                 }
-                query.push_str(&format!(
-                    "(${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${})",
-                    i * columns + 1,
-                    i * columns + 2,
-                    i * columns + 3,
-                    i * columns + 4,
-                    i * columns + 5,
-                    i * columns + 6,
-                    i * columns + 7,
-                    i * columns + 8,
-                    i * columns + 9,
-                    i * columns + 10,
-                    i * columns + 11,
-                    i * columns + 12,
-                    i * columns + 13,
-                    i * columns + 14,
-                    i * columns + 15
-                ));
+                let placeholders = (1..=columns)
+                    .map(|c| format!("${}", i * columns + c))
+                    .collect::<Vec<String>>();
+                query.push_str(&format!("({})", placeholders.join(", ")));
                 params.push(&data.architecture);
+                params.push(&data.build);
                 params.push(&data.checksum);
                 params.push(&data.checksum_url);
+                params.push(&data.component);
                 params.push(&data.features);
                 params.push(&data.file_type);
                 params.push(&data.filename);
                 params.push(&data.image_type);
                 params.push(&data.java_version);
                 params.push(&data.jvm_impl);
+                params.push(&data.major);
+                params.push(&data.minor);
+                params.push(&data.openjdk_version);
                 params.push(&data.os);
+                params.push(&data.patch);
+                params.push(&data.platform_requirements);
                 params.push(&data.release_type);
                 params.push(&data.size);
                 params.push(&data.url);
@@ -74,16 +221,23 @@ impl JvmRepository {
             query.push_str(
                 " ON CONFLICT(url) DO UPDATE SET
                 architecture = excluded.architecture,
+                build = excluded.build,
                 checksum = excluded.checksum,
                 checksum_url = excluded.checksum_url,
+                component = excluded.component,
                 features = excluded.features,
                 file_type = excluded.file_type,
                 filename = excluded.filename,
                 image_type = excluded.image_type,
                 java_version = excluded.java_version,
                 jvm_impl = excluded.jvm_impl,
-                modified_at = CURRENT_TIMESTAMP,
+                major = excluded.major,
+                minor = excluded.minor,
+                modified_at = RFC3339_UTC(CURRENT_TIMESTAMP),
+                openjdk_version = excluded.openjdk_version,
                 os = excluded.os,
+                patch = excluded.patch,
+                platform_requirements = excluded.platform_requirements,
                 release_type = excluded.release_type,
                 size = excluded.size,
                 url = excluded.url,
@@ -91,15 +245,22 @@ impl JvmRepository {
                 version = excluded.version
                 WHERE
                    excluded.architecture != JVM.architecture
+                OR excluded.build IS DISTINCT FROM JVM.build
                 OR excluded.checksum != JVM.checksum
                 OR excluded.checksum_url != JVM.checksum_url
-                OR excluded.features != JVM.features
+                OR excluded.component != JVM.component
+                OR excluded.features IS DISTINCT FROM JVM.features
                 OR excluded.file_type != JVM.file_type
                 OR excluded.filename != JVM.filename
                 OR excluded.image_type != JVM.image_type
                 OR excluded.java_version != JVM.java_version
                 OR excluded.jvm_impl != JVM.jvm_impl
+                OR excluded.major IS DISTINCT FROM JVM.major
+                OR excluded.minor IS DISTINCT FROM JVM.minor
+                OR excluded.openjdk_version IS DISTINCT FROM JVM.openjdk_version
                 OR excluded.os != JVM.os
+                OR excluded.patch IS DISTINCT FROM JVM.patch
+                OR excluded.platform_requirements IS DISTINCT FROM JVM.platform_requirements
                 OR excluded.release_type != JVM.release_type
                 OR excluded.size != JVM.size
                 OR excluded.url != JVM.url
@@ -111,6 +272,13 @@ impl JvmRepository {
             result += tx.execute(&query, &params)?;
         }
 
+        // unchanged records are not updated above but were still found at the vendor
+        let urls = jvm_data.iter().map(|data| data.url.as_str()).collect::<Vec<&str>>();
+        tx.execute(
+            "UPDATE JVM SET verified_at = RFC3339_UTC(CURRENT_TIMESTAMP) WHERE url = ANY($1)",
+            &[&urls],
+        )?;
+
         tx.commit()?;
         Ok(result)
     }
@@ -119,15 +287,23 @@ impl JvmRepository {
         let stmt = indoc! {
           "SELECT
               architecture,
+              build,
               checksum,
               checksum_url,
+              component,
               features,
               file_type,
               filename,
               image_type,
               java_version,
               jvm_impl,
+              major,
+              minor,
+              verified_at,
+              openjdk_version,
               os,
+              patch,
+              platform_requirements,
               release_type,
               size,
               url,
@@ -149,15 +325,23 @@ impl JvmRepository {
         let stmt = indoc::indoc! {
           "SELECT
               architecture,
+              build,
               checksum,
               checksum_url,
+              component,
               features,
               file_type,
               filename,
               image_type,
               java_version,
               jvm_impl,
+              major,
+              minor,
+              verified_at,
+              openjdk_version,
               os,
+              patch,
+              platform_requirements,
               release_type,
               size,
               url,
@@ -175,35 +359,247 @@ impl JvmRepository {
         self.export(stmt, &[&vendor, &os, &arch])
     }
 
+    /// Exports the releases of a major Java version, the predicate matches the `JVM_IDX_VENDOR_MAJOR` index
+    pub fn export_vendor_major(&self, vendor: &str, major: i32, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        let stmt = indoc! {
+          "SELECT
+              architecture,
+              build,
+              checksum,
+              checksum_url,
+              component,
+              features,
+              file_type,
+              filename,
+              image_type,
+              java_version,
+              jvm_impl,
+              major,
+              minor,
+              verified_at,
+              openjdk_version,
+              os,
+              patch,
+              platform_requirements,
+              release_type,
+              size,
+              url,
+              vendor,
+              version
+          FROM
+              JVM
+          WHERE
+              vendor = $1
+              AND major = $2
+              AND os = $3
+              AND architecture = $4
+          ;",
+        };
+
+        self.export(stmt, &[&vendor, &major, &os, &arch])
+    }
+
+    pub fn export_all(&self) -> Result<Vec<JvmData>> {
+        let stmt = indoc! {
+          "SELECT
+              architecture,
+              build,
+              checksum,
+              checksum_url,
+              component,
+              features,
+              file_type,
+              filename,
+              image_type,
+              java_version,
+              jvm_impl,
+              major,
+              minor,
+              verified_at,
+              openjdk_version,
+              os,
+              patch,
+              platform_requirements,
+              release_type,
+              size,
+              url,
+              vendor,
+              version
+          FROM
+              JVM
+          ;",
+        };
+
+        self.export(stmt, &[])
+    }
+
+    pub fn export_os(&self, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        let stmt = indoc! {
+          "SELECT
+              architecture,
+              build,
+              checksum,
+              checksum_url,
+              component,
+              features,
+              file_type,
+              filename,
+              image_type,
+              java_version,
+              jvm_impl,
+              major,
+              minor,
+              verified_at,
+              openjdk_version,
+              os,
+              patch,
+              platform_requirements,
+              release_type,
+              size,
+              url,
+              vendor,
+              version
+          FROM
+              JVM
+          WHERE
+              os = $1
+              AND architecture = $2
+          ;",
+        };
+
+        self.export(stmt, &[&os, &arch])
+    }
+
+    /// Queries a page of records matching the filters in form of column => value or !value
+    pub fn query(&self, filters: &HashMap<String, Vec<String>>, page: &Page) -> Result<Vec<JvmData>> {
+        let (clauses, values) = query_clauses(filters, page)?;
+        let stmt = formatdoc! {
+          "SELECT
+              architecture,
+              build,
+              checksum,
+              checksum_url,
+              component,
+              features,
+              file_type,
+              filename,
+              image_type,
+              java_version,
+              jvm_impl,
+              major,
+              minor,
+              verified_at,
+              openjdk_version,
+              os,
+              patch,
+              platform_requirements,
+              release_type,
+              size,
+              url,
+              vendor,
+              version
+          FROM
+              JVM
+          {}
+          ;",
+          clauses,
+        };
+        let params = values
+            .iter()
+            .map(|v| v as &(dyn postgres::types::ToSql + Sync))
+            .collect::<Vec<_>>();
+        self.query_rows(&stmt, &params)
+    }
+
     fn export(&self, query: &str, params: &[&(dyn postgres::types::ToSql + Sync)]) -> Result<Vec<JvmData>> {
+        let mut data = self.query_rows(query, params)?;
+        // rows are sorted to export files in the same order regardless of the query plan
+        data.sort_by(|a, b| a.url.cmp(&b.url));
+        Ok(data)
+    }
+
+    fn query_rows(&self, query: &str, params: &[&(dyn postgres::types::ToSql + Sync)]) -> Result<Vec<JvmData>> {
         let mut conn = self.pool.get()?;
         let stmt = conn.prepare(query)?;
         let mut data = Vec::new();
         let rows = conn.query(&stmt, params)?;
+        let now = Utc::now();
         for row in rows {
-            data.push(JvmData {
-                architecture: row.get("architecture"),
+            let architecture: String = row.get("architecture");
+            let mut item = JvmData {
+                age_days: freshness::age_days(row.get("verified_at"), now),
+                bitness: arch::bitness(&architecture),
+                endianness: arch::endianness(&architecture).map(String::from),
+                architecture,
+                build: row.get("build"),
                 checksum: row.get("checksum"),
                 checksum_url: row.get("checksum_url"),
-                features: row
-                    .get::<_, Option<String>>("features")
-                    .map(|f| f.split(',').map(String::from).collect()),
+                component: row.get("component"),
+                distribution_notes: None,
+                features: get_features(&row),
                 file_type: row.get("file_type"),
                 filename: row.get("filename"),
+                id: None,
                 image_type: row.get("image_type"),
+                installer_kind: None,
                 java_version: row.get("java_version"),
                 jvm_impl: row.get("jvm_impl"),
+                major: row.get("major"),
+                minor: row.get("minor"),
+                openjdk_version: row.get("openjdk_version"),
                 os: row.get("os"),
+                patch: row.get("patch"),
+                platform_requirements: row
+                    .get::<_, Option<Value>>("platform_requirements")
+                    .and_then(|p| serde_json::from_value(p).ok()),
                 release_type: row.get("release_type"),
+                restricted: false,
                 size: row.get::<_, Option<i32>>("size"),
                 url: row.get("url"),
                 vendor: row.get("vendor"),
                 version: row.get("version"),
-            });
+            };
+            item.id = Some(JvmData::id(&item.url));
+            item.installer_kind = installer::installer_kind(&item);
+            distribution::apply(&mut item);
+            data.push(item);
         }
         Ok(data)
     }
 
+    /// Removes the checksum URLs which returned 404 in at least `min_failures` fetches
+    pub fn prune_checksum_urls(&self, min_failures: i32) -> Result<u64> {
+        let mut conn = self.pool.get()?;
+        let stmt = indoc! {
+          "UPDATE JVM SET
+              checksum_url = NULL,
+              modified_at = RFC3339_UTC(CURRENT_TIMESTAMP)
+          WHERE
+              checksum_url IN (SELECT url FROM NOT_FOUND WHERE failures >= $1)
+          ;",
+        };
+        Ok(conn.execute(stmt, &[&min_failures])?)
+    }
+
+    pub fn count_by_vendor(&self) -> Result<BTreeMap<String, u64>> {
+        let mut conn = self.pool.get()?;
+        let rows = conn.query("SELECT vendor, COUNT(*) FROM JVM GROUP BY vendor;", &[])?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get::<_, String>(0), row.get::<_, i64>(1) as u64))
+            .collect())
+    }
+
+    /// Returns the modified_at of all records by URL
+    pub fn get_modified_at(&self) -> Result<HashMap<String, String>> {
+        let mut conn = self.pool.get()?;
+        let rows = conn.query("SELECT url, modified_at FROM JVM;", &[])?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("url"), row.get("modified_at")))
+            .collect())
+    }
+
     pub fn get_distinct(&self, column: &str) -> Result<Vec<String>> {
         let mut conn = self.pool.get()?;
         let stmt = conn.prepare(&format!("SELECT DISTINCT {} FROM JVM ORDER BY {} ASC;", column, column))?;
@@ -216,18 +612,106 @@ impl JvmRepository {
     }
 }
 
+/// Returns the WHERE, ORDER BY, LIMIT and OFFSET clauses of a query and the values of its parameters
+///
+/// The url is always sorted last, so that pages of the same query don't overlap.
+fn query_clauses(filters: &HashMap<String, Vec<String>>, page: &Page) -> Result<(String, Vec<Vec<String>>)> {
+    let mut conditions = Vec::new();
+    let mut values = Vec::new();
+    let mut columns = filters.keys().collect::<Vec<_>>();
+    columns.sort();
+    for column in columns {
+        if !FILTER_COLUMNS.contains(&column.as_str()) {
+            return Err(eyre::eyre!(
+                "unable to filter by {}, use one of {}",
+                column,
+                FILTER_COLUMNS.join(", ")
+            ));
+        }
+        let (neq, eq): (Vec<String>, Vec<String>) = filters[column].iter().cloned().partition(|v| v.starts_with('!'));
+        // records without features match any feature filter like `JvmData::filter`
+        let features = column == "features";
+        if !eq.is_empty() {
+            values.push(eq);
+            conditions.push(match features {
+                true => format!(
+                    "(features IS NULL OR jsonb_array_length(features) = 0 OR features ?| ${})",
+                    values.len()
+                ),
+                false => format!("{} = ANY(${})", column, values.len()),
+            });
+        }
+        if !neq.is_empty() {
+            values.push(neq.iter().map(|v| v[1..].to_string()).collect());
+            conditions.push(match features {
+                true => format!("(features IS NULL OR NOT features ?| ${})", values.len()),
+                false => format!("NOT {} = ANY(${})", column, values.len()),
+            });
+        }
+    }
+
+    let mut clauses = Vec::new();
+    if !conditions.is_empty() {
+        clauses.push(format!("WHERE {}", conditions.join(" AND ")));
+    }
+    let order = page
+        .sort
+        .iter()
+        .map(Sort::to_sql)
+        .chain(["url ASC".to_string()])
+        .collect::<Vec<_>>();
+    clauses.push(format!("ORDER BY {}", order.join(", ")));
+    if let Some(limit) = page.limit {
+        clauses.push(format!("LIMIT {}", limit));
+    }
+    if page.offset > 0 {
+        clauses.push(format!("OFFSET {}", page.offset));
+    }
+    Ok((clauses.join(" "), values))
+}
+
+/// Returns the features of a row, stored as JSON array or comma-joined by databases not migrated yet
+fn get_features(row: &postgres::Row) -> Option<Vec<String>> {
+    match row.try_get::<_, Option<Value>>("features") {
+        Ok(features) => features.and_then(parse_features),
+        Err(_) => row
+            .get::<_, Option<String>>("features")
+            .and_then(|f| parse_features(Value::String(f))),
+    }
+}
+
+fn parse_features(features: Value) -> Option<Vec<String>> {
+    match features {
+        Value::Array(features) => Some(
+            features
+                .into_iter()
+                .filter_map(|f| f.as_str().map(String::from))
+                .collect(),
+        ),
+        Value::String(features) if !features.is_empty() => Some(features.split(',').map(String::from).collect()),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 struct DbJvmData {
     pub architecture: String,
+    pub build: Option<i32>,
     pub checksum: Option<String>,
     pub checksum_url: Option<String>,
-    pub features: Option<String>,
+    pub component: String,
+    pub features: Option<Value>,
     pub file_type: String,
     pub filename: String,
     pub image_type: String,
     pub java_version: String,
     pub jvm_impl: String,
+    pub major: Option<i32>,
+    pub minor: Option<i32>,
+    pub openjdk_version: Option<String>,
     pub os: String,
+    pub patch: Option<i32>,
+    pub platform_requirements: Option<Value>,
     pub release_type: String,
     pub size: Option<i32>,
     pub url: String,
@@ -236,27 +720,185 @@ struct DbJvmData {
 }
 
 fn map_workaround(jvm_data: &HashSet<JvmData>) -> Vec<DbJvmData> {
-    jvm_data
+    let mut data = jvm_data
         .iter()
-        // workaround for the `feature` field which needs to be joined
+        // workaround for the `feature` field which needs to be converted to JSON
         // and therefore would not live long enough in context of a
-        // batch insert
-        .map(|item| DbJvmData {
+        // batch insert, the version columns are derived by the shared version parser
+        // and the platform requirements by the vendor rules
+        .map(|item| (item, version::parse(&item.java_version)))
+        .map(|(item, java_version)| DbJvmData {
             architecture: item.architecture.clone(),
+            build: java_version.build,
             checksum: item.checksum.clone(),
             checksum_url: item.checksum_url.clone(),
-            features: item.features.as_ref().map(|f| f.join(",")),
+            component: item.component.clone(),
+            features: item.features.as_ref().map(|f| Value::from(f.clone())),
             file_type: item.file_type.clone(),
             filename: item.filename.clone(),
             image_type: item.image_type.clone(),
             java_version: item.java_version.clone(),
             jvm_impl: item.jvm_impl.clone(),
+            major: java_version.major,
+            minor: java_version.minor,
+            openjdk_version: java_version.openjdk_version,
             os: item.os.clone(),
+            patch: java_version.patch,
+            platform_requirements: item
+                .platform_requirements
+                .clone()
+                .or_else(|| libc::platform_requirements(item))
+                .and_then(|p| serde_json::to_value(p).ok()),
             release_type: item.release_type.clone(),
             size: item.size,
             url: item.url.clone(),
             vendor: item.vendor.clone(),
             version: item.version.clone(),
         })
-        .collect::<Vec<DbJvmData>>()
+        .collect::<Vec<DbJvmData>>();
+    // the data is sorted to insert the same batches on every run
+    data.sort_by(|a, b| a.url.cmp(&b.url));
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sort_from_str() {
+        for (actual, expected) in [
+            ("version:desc", Ok(("version", true))),
+            ("size:asc", Ok(("size", false))),
+            ("vendor", Ok(("vendor", false))),
+            ("version:up", Err(())),
+            ("checksum", Err(())),
+        ] {
+            let sort = Sort::from_str(actual).map(|s| (s.column, s.descending)).map_err(|_| ());
+            assert_eq!(sort, expected.map(|(c, d)| (c.to_string(), d)));
+        }
+    }
+
+    #[test]
+    fn test_query_clauses() {
+        let filters = HashMap::from([
+            ("vendor".to_string(), vec!["temurin".to_string(), "zulu".to_string()]),
+            ("os".to_string(), vec!["!windows".to_string()]),
+        ]);
+        let page = Page {
+            sort: vec![Sort::from_str("version:desc").unwrap()],
+            limit: Some(20),
+            offset: 40,
+        };
+
+        let (clauses, values) = query_clauses(&filters, &page).unwrap();
+
+        assert_eq!(
+            clauses,
+            "WHERE NOT os = ANY($1) AND vendor = ANY($2) \
+             ORDER BY major DESC NULLS LAST, minor DESC NULLS LAST, patch DESC NULLS LAST, build DESC NULLS LAST, \
+             version DESC, url ASC LIMIT 20 OFFSET 40"
+        );
+        assert_eq!(
+            values,
+            vec![
+                vec!["windows".to_string()],
+                vec!["temurin".to_string(), "zulu".to_string()]
+            ]
+        );
+
+        let (clauses, values) = query_clauses(&HashMap::new(), &Page::default()).unwrap();
+        assert_eq!(clauses, "ORDER BY url ASC");
+        assert!(values.is_empty());
+
+        let filters = HashMap::from([("checksum".to_string(), vec!["sha256:abc".to_string()])]);
+        assert!(query_clauses(&filters, &Page::default()).is_err());
+
+        let filters = HashMap::from([("features".to_string(), vec!["musl".to_string(), "!debug".to_string()])]);
+        let (clauses, _) = query_clauses(&filters, &Page::default()).unwrap();
+        assert_eq!(
+            clauses,
+            "WHERE (features IS NULL OR jsonb_array_length(features) = 0 OR features ?| $1) \
+             AND (features IS NULL OR NOT features ?| $2) ORDER BY url ASC"
+        );
+    }
+
+    #[test]
+    fn test_page_apply() {
+        let data = [
+            ("a", Some(17), "17.0.9"),
+            ("b", Some(21), "21.0.2"),
+            ("c", None, "unknown"),
+            ("d", Some(21), "21.0.10"),
+        ]
+        .into_iter()
+        .map(|(url, major, version)| JvmData {
+            url: url.to_string(),
+            major,
+            minor: major.map(|_| 0),
+            patch: version.rsplit('.').next().and_then(|p| p.parse().ok()),
+            version: version.to_string(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+        let urls = |data: Vec<JvmData>| data.into_iter().map(|d| d.url).collect::<Vec<_>>();
+
+        assert_eq!(urls(Page::default().apply(data.clone())), vec!["a", "b", "c", "d"]);
+
+        let page = Page {
+            sort: vec![Sort::from_str("version:desc").unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(urls(page.apply(data.clone())), vec!["d", "b", "a", "c"]);
+
+        let page = Page {
+            sort: vec![Sort::from_str("major").unwrap()],
+            limit: Some(2),
+            offset: 1,
+        };
+        assert_eq!(urls(page.apply(data)), vec!["b", "d"]);
+    }
+
+    #[test]
+    fn test_parse_features() {
+        for (actual, expected) in [
+            (json!(["lite", "musl"]), Some(vec!["lite", "musl"])),
+            (json!([]), Some(vec![])),
+            (json!("lite,musl"), Some(vec!["lite", "musl"])),
+            (json!("musl"), Some(vec!["musl"])),
+            (json!(""), None),
+            (Value::Null, None),
+        ] {
+            let expected = expected.map(|f| f.into_iter().map(String::from).collect::<Vec<_>>());
+            assert_eq!(parse_features(actual), expected);
+        }
+    }
+
+    #[test]
+    fn test_map_workaround_conflict_key() {
+        let jvm_data = [
+            "https://example.com/b.zip",
+            "https://example.com/a.zip",
+            "https://example.com/b.zip",
+        ]
+        .iter()
+        .map(|url| JvmData {
+            java_version: "21.0.2+13".to_string(),
+            url: url.to_string(),
+            ..Default::default()
+        })
+        .collect::<HashSet<JvmData>>();
+
+        let data = map_workaround(&jvm_data);
+
+        // a batch must not contain the conflict key twice, ON CONFLICT(url) DO UPDATE fails otherwise
+        assert_eq!(
+            data.iter().map(|d| d.url.as_str()).collect::<Vec<&str>>(),
+            vec!["https://example.com/a.zip", "https://example.com/b.zip"]
+        );
+        assert_eq!(data[0].major, Some(21));
+        assert_eq!(data[0].build, Some(13));
+    }
 }
@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use eyre::Result;
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+
+use super::jvm_repository::JvmRepository;
+use crate::jvm::JvmData;
+
+/// Reads the JVM data of several databases as if they were one, see `export.databases`
+///
+/// Records of the same URL are merged, the record with the newest modified_at wins and the database configured first
+/// wins a tie. A single database is read through without merging.
+pub struct MergedJvmRepository {
+    repositories: Vec<JvmRepository>,
+    /// modified_at of the records by URL, per database
+    modified_at: Vec<HashMap<String, String>>,
+}
+
+impl MergedJvmRepository {
+    pub fn new(pools: Vec<Pool<PostgresConnectionManager<MakeTlsConnector>>>) -> Result<Self> {
+        let repositories = pools.into_iter().map(JvmRepository::new).collect::<Result<Vec<_>>>()?;
+        let modified_at = match repositories.len() {
+            1 => Vec::new(),
+            _ => repositories
+                .iter()
+                .map(|repository| repository.get_modified_at())
+                .collect::<Result<Vec<_>>>()?,
+        };
+        Ok(MergedJvmRepository {
+            repositories,
+            modified_at,
+        })
+    }
+
+    pub fn export_all(&self) -> Result<Vec<JvmData>> {
+        self.merge(|repository| repository.export_all())
+    }
+
+    pub fn export_os(&self, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        self.merge(|repository| repository.export_os(os, arch))
+    }
+
+    pub fn export_release_type(&self, release_type: &str, arch: &str, os: &str) -> Result<Vec<JvmData>> {
+        self.merge(|repository| repository.export_release_type(release_type, arch, os))
+    }
+
+    pub fn export_vendor(&self, vendor: &str, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        self.merge(|repository| repository.export_vendor(vendor, os, arch))
+    }
+
+    pub fn export_vendor_major(&self, vendor: &str, major: i32, os: &str, arch: &str) -> Result<Vec<JvmData>> {
+        self.merge(|repository| repository.export_vendor_major(vendor, major, os, arch))
+    }
+
+    pub fn get_distinct(&self, column: &str) -> Result<Vec<String>> {
+        let mut values = BTreeSet::new();
+        for repository in &self.repositories {
+            values.extend(repository.get_distinct(column)?);
+        }
+        Ok(values.into_iter().collect())
+    }
+
+    fn merge<F>(&self, export: F) -> Result<Vec<JvmData>>
+    where
+        F: Fn(&JvmRepository) -> Result<Vec<JvmData>>,
+    {
+        if let [repository] = self.repositories.as_slice() {
+            return export(repository);
+        }
+        let sources = self.repositories.iter().map(export).collect::<Result<Vec<_>>>()?;
+        Ok(merge_records(
+            sources.into_iter().zip(self.modified_at.iter()).collect(),
+        ))
+    }
+}
+
+/// Merges the records of several databases, sorted by URL like the exports of a single database
+///
+/// The record with the newest modified_at wins, the source listed first wins a tie. modified_at is stored as UTC
+/// RFC3339 and compares in order of time as text.
+fn merge_records(sources: Vec<(Vec<JvmData>, &HashMap<String, String>)>) -> Vec<JvmData> {
+    let mut merged: BTreeMap<String, (Option<&String>, JvmData)> = BTreeMap::new();
+    for (records, modified_at) in sources {
+        for record in records {
+            let modified = modified_at.get(&record.url);
+            if merged.get(&record.url).is_none_or(|(newest, _)| modified > *newest) {
+                merged.insert(record.url.clone(), (modified, record));
+            }
+        }
+    }
+    merged.into_values().map(|(_, record)| record).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_jvmdata(url: &str, version: &str) -> JvmData {
+        JvmData {
+            url: url.to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_records() {
+        let prod = vec![
+            get_jvmdata("https://example.com/a.tar.gz", "prod"),
+            get_jvmdata("https://example.com/c.tar.gz", "prod"),
+            get_jvmdata("https://example.com/d.tar.gz", "prod"),
+        ];
+        let prod_modified_at = HashMap::from([
+            (
+                "https://example.com/a.tar.gz".to_string(),
+                "2025-01-31T12:00:00Z".to_string(),
+            ),
+            (
+                "https://example.com/c.tar.gz".to_string(),
+                "2025-03-01T08:00:00Z".to_string(),
+            ),
+            (
+                "https://example.com/d.tar.gz".to_string(),
+                "2025-02-01T00:00:00Z".to_string(),
+            ),
+        ]);
+        let staging = vec![
+            get_jvmdata("https://example.com/a.tar.gz", "staging"),
+            get_jvmdata("https://example.com/b.tar.gz", "staging"),
+            get_jvmdata("https://example.com/c.tar.gz", "staging"),
+            get_jvmdata("https://example.com/d.tar.gz", "staging"),
+        ];
+        let staging_modified_at = HashMap::from([
+            (
+                "https://example.com/a.tar.gz".to_string(),
+                "2025-02-15T10:00:00Z".to_string(),
+            ),
+            (
+                "https://example.com/b.tar.gz".to_string(),
+                "2025-01-01T00:00:00Z".to_string(),
+            ),
+            (
+                "https://example.com/c.tar.gz".to_string(),
+                "2025-02-28T23:59:59Z".to_string(),
+            ),
+            (
+                "https://example.com/d.tar.gz".to_string(),
+                "2025-02-01T00:00:00Z".to_string(),
+            ),
+        ]);
+
+        let merged = merge_records(vec![(prod, &prod_modified_at), (staging, &staging_modified_at)]);
+
+        assert_eq!(
+            merged
+                .iter()
+                .map(|d| (d.url.as_str(), d.version.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("https://example.com/a.tar.gz", "staging"),
+                ("https://example.com/b.tar.gz", "staging"),
+                ("https://example.com/c.tar.gz", "prod"),
+                // a tie is won by the database configured first
+                ("https://example.com/d.tar.gz", "prod"),
+            ]
+        );
+    }
+}
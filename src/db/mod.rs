@@ -1,2 +1,10 @@
+pub mod asset_repository;
+pub mod build_tool_repository;
+pub mod fetch_count_repository;
 pub mod jvm_repository;
+pub mod merged_jvm_repository;
+pub mod not_found_repository;
 pub mod pool;
+#[cfg(test)]
+mod postgres_tests;
+pub mod stats_repository;
@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use eyre::Result;
+use indoc::indoc;
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+
+/// Number of fetches a URL must have returned 404 in to be considered a permanent failure
+pub const PERMANENT_FAILURES: i32 = 3;
+
+/// Days after which a permanent failure is requested again, in case the vendor fixed it
+pub const RETRY_DAYS: i32 = 30;
+
+pub struct NotFoundRepository {
+    pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
+}
+
+impl NotFoundRepository {
+    pub fn new(pool: Pool<PostgresConnectionManager<MakeTlsConnector>>) -> Result<Self> {
+        Ok(NotFoundRepository { pool })
+    }
+
+    /// Records URLs which returned 404, the failures of known URLs are incremented
+    pub fn insert(&self, urls: &[String]) -> Result<u64> {
+        let mut conn = self.pool.get()?;
+        let mut tx = conn.transaction()?;
+        let stmt = tx.prepare(indoc! {
+          "INSERT INTO NOT_FOUND (url) VALUES ($1)
+          ON CONFLICT(url) DO UPDATE SET
+              failures = NOT_FOUND.failures + 1,
+              last_seen_at = RFC3339_UTC(CURRENT_TIMESTAMP)
+          ;"
+        })?;
+        let mut result = 0;
+        for url in urls {
+            result += tx.execute(&stmt, &[url])?;
+        }
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Returns the URLs which returned 404 in at least `min_failures` fetches, the last one within `days`
+    pub fn get_permanent(&self, min_failures: i32, days: i32) -> Result<HashSet<String>> {
+        let mut conn = self.pool.get()?;
+        let stmt = indoc! {
+          "SELECT
+              url
+          FROM
+              NOT_FOUND
+          WHERE
+              failures >= $1
+              AND last_seen_at::TIMESTAMPTZ >= NOW() - make_interval(days => $2)
+          ;",
+        };
+        let rows = conn.query(stmt, &[&min_failures, &days])?;
+        Ok(rows.iter().map(|row| row.get("url")).collect())
+    }
+}
@@ -13,54 +13,79 @@ pub struct ConnectionPool {}
 impl ConnectionPool {
     pub fn get_pool() -> Result<Pool<PostgresConnectionManager<MakeTlsConnector>>> {
         let conf: Conf = Conf::try_get()?;
+        let password = conf.database.password()?;
 
-        match conf.database.url {
-            Some(url) => {
-                if url.starts_with("postgres://") {
-                    let mut connector = SslConnector::builder(SslMethod::tls())?;
-                    match conf
-                        .database
-                        .ssl_mode
-                        .unwrap_or("prefer".to_string())
-                        .to_lowercase()
-                        .as_ref()
-                    {
-                        "require" | "prefer" | "allow" => connector.set_verify(openssl::ssl::SslVerifyMode::NONE),
-                        "verify-ca" => {
-                            connector.set_verify(openssl::ssl::SslVerifyMode::PEER);
-                            // disable hostname verification
-                            connector.set_verify_callback(openssl::ssl::SslVerifyMode::PEER, |_, _| true);
-                            connector.set_ca_file(conf.database.ssl_ca.expect("database.ssl_ca is not configured"))?;
-                        }
-                        // default to the verify-full
-                        _ => {
-                            connector.set_verify(
-                                openssl::ssl::SslVerifyMode::PEER | openssl::ssl::SslVerifyMode::FAIL_IF_NO_PEER_CERT,
-                            );
-                            // disable hostname verification
-                            connector.set_verify_callback(openssl::ssl::SslVerifyMode::PEER, |_, _| true);
-                            connector.set_ca_file(conf.database.ssl_ca.expect("database.ssl_ca is not configured"))?;
-                            connector.set_certificate_chain_file(
-                                conf.database.ssl_cert.expect("database.ssl_cert is not configured"),
-                            )?;
-                            connector.set_private_key_file(
-                                conf.database.ssl_key.expect("database.ssl_key is not configured"),
-                                openssl::ssl::SslFiletype::PEM,
-                            )?;
-                        }
-                    }
-                    let tls_connector = MakeTlsConnector::new(connector.build());
-                    let manager = PostgresConnectionManager::new(url.parse().unwrap(), tls_connector);
-                    let pool = Pool::builder()
-                        .max_size(conf.database.pool_size.unwrap_or(10))
-                        .max_lifetime(Some(Duration::from_secs(60 * 60)))
-                        .build(manager)?;
-                    Ok(pool)
-                } else {
-                    Err(eyre::eyre!("unsupported database URL: {}", url))
-                }
-            }
+        match &conf.database.url {
+            Some(url) => Self::build(&conf, url, password),
             None => Err(eyre::eyre!("database.url is not configured")),
         }
     }
+
+    /// Returns the pools of the databases to export from, the configured database followed by `export.databases`
+    ///
+    /// The additional databases share the SSL settings of the configured one, their passwords are part of the URLs
+    pub fn get_export_pools() -> Result<Vec<Pool<PostgresConnectionManager<MakeTlsConnector>>>> {
+        let conf: Conf = Conf::try_get()?;
+        let mut pools = vec![Self::get_pool()?];
+        for url in conf.export.databases.iter().flatten() {
+            pools.push(Self::build(&conf, url, None)?);
+        }
+        Ok(pools)
+    }
+
+    fn build(
+        conf: &Conf,
+        url: &str,
+        password: Option<String>,
+    ) -> Result<Pool<PostgresConnectionManager<MakeTlsConnector>>> {
+        if !url.starts_with("postgres://") {
+            return Err(eyre::eyre!("unsupported database URL: {}", url));
+        }
+        let database = &conf.database;
+        let mut connector = SslConnector::builder(SslMethod::tls())?;
+        match database
+            .ssl_mode
+            .clone()
+            .unwrap_or("prefer".to_string())
+            .to_lowercase()
+            .as_ref()
+        {
+            "require" | "prefer" | "allow" => connector.set_verify(openssl::ssl::SslVerifyMode::NONE),
+            "verify-ca" => {
+                connector.set_verify(openssl::ssl::SslVerifyMode::PEER);
+                // disable hostname verification
+                connector.set_verify_callback(openssl::ssl::SslVerifyMode::PEER, |_, _| true);
+                connector.set_ca_file(database.ssl_ca.as_deref().expect("database.ssl_ca is not configured"))?;
+            }
+            // default to the verify-full
+            _ => {
+                connector
+                    .set_verify(openssl::ssl::SslVerifyMode::PEER | openssl::ssl::SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+                // disable hostname verification
+                connector.set_verify_callback(openssl::ssl::SslVerifyMode::PEER, |_, _| true);
+                connector.set_ca_file(database.ssl_ca.as_deref().expect("database.ssl_ca is not configured"))?;
+                connector.set_certificate_chain_file(
+                    database
+                        .ssl_cert
+                        .as_deref()
+                        .expect("database.ssl_cert is not configured"),
+                )?;
+                connector.set_private_key_file(
+                    database.ssl_key.as_deref().expect("database.ssl_key is not configured"),
+                    openssl::ssl::SslFiletype::PEM,
+                )?;
+            }
+        }
+        let tls_connector = MakeTlsConnector::new(connector.build());
+        let mut config: postgres::Config = url.parse()?;
+        if let Some(password) = password {
+            config.password(password);
+        }
+        let manager = PostgresConnectionManager::new(config, tls_connector);
+        let pool = Pool::builder()
+            .max_size(database.pool_size.unwrap_or(10))
+            .max_lifetime(Some(Duration::from_secs(60 * 60)))
+            .build(manager)?;
+        Ok(pool)
+    }
 }
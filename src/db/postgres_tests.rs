@@ -0,0 +1,323 @@
+// Integration tests of the repositories against PostgreSQL started by testcontainers
+// The databases are upgraded from the baseline schema by the migrations, as existing installations are
+// The tests require docker and are ignored by default, run them with `cargo test -- --ignored`
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use openssl::ssl::{SslConnector, SslMethod};
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+use testcontainers_modules::{
+    postgres::Postgres,
+    testcontainers::{Container, runners::SyncRunner},
+};
+
+use super::{
+    asset_repository::AssetRepository,
+    fetch_count_repository::FetchCountRepository,
+    jvm_repository::{JvmRepository, Page},
+    not_found_repository::NotFoundRepository,
+};
+use crate::jvm::{JvmData, vendor::AssetChecksum};
+
+/// A PostgreSQL container, removed on drop
+struct Database {
+    _container: Container<Postgres>,
+    pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
+}
+
+impl Database {
+    /// Starts a database upgraded from `sql/baseline.sql` by the scripts of `sql/migrations` in order, the way
+    /// existing databases are upgraded
+    fn start() -> Self {
+        let db = Database::empty();
+        let mut conn = db.pool.get().unwrap();
+        conn.batch_execute(include_str!("../../sql/baseline.sql")).unwrap();
+        for migration in migrations() {
+            conn.batch_execute(&std::fs::read_to_string(&migration).unwrap())
+                .unwrap_or_else(|e| panic!("unable to apply {}: {}", migration.display(), e));
+        }
+        db
+    }
+
+    /// Starts a database created by `sql/schema.sql` like a new installation
+    fn start_with_schema() -> Self {
+        let db = Database::empty();
+        let mut conn = db.pool.get().unwrap();
+        conn.batch_execute(include_str!("../../sql/schema.sql")).unwrap();
+        db
+    }
+
+    fn empty() -> Self {
+        let container = Postgres::default()
+            .start()
+            .expect("unable to start PostgreSQL, is docker running?");
+        let url = format!(
+            "postgres://postgres:postgres@{}:{}/postgres",
+            container.get_host().unwrap(),
+            container.get_host_port_ipv4(5432).unwrap()
+        );
+        let connector = MakeTlsConnector::new(SslConnector::builder(SslMethod::tls()).unwrap().build());
+        let manager = PostgresConnectionManager::new(url.parse().unwrap(), connector);
+        let pool = Pool::builder().max_size(2).build(manager).unwrap();
+        // the schema grants access to the application user
+        pool.get().unwrap().batch_execute("CREATE ROLE roast;").unwrap();
+        Database {
+            _container: container,
+            pool,
+        }
+    }
+
+    /// Returns the columns of all tables in form of table.column type default nullable
+    fn columns(&self) -> Vec<String> {
+        let mut conn = self.pool.get().unwrap();
+        let rows = conn
+            .query(
+                "SELECT table_name::TEXT, column_name::TEXT, data_type::TEXT, column_default::TEXT, is_nullable::TEXT
+                FROM information_schema.columns
+                WHERE table_schema = 'public'
+                ORDER BY table_name, column_name",
+                &[],
+            )
+            .unwrap();
+        rows.iter()
+            .map(|row| {
+                format!(
+                    "{}.{} {} {} {}",
+                    row.get::<_, String>(0),
+                    row.get::<_, String>(1),
+                    row.get::<_, String>(2),
+                    row.get::<_, Option<String>>(3).unwrap_or_default(),
+                    row.get::<_, String>(4)
+                )
+            })
+            .collect()
+    }
+}
+
+/// Returns the scripts of `sql/migrations` in order
+fn migrations() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("sql/migrations");
+    let mut migrations = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect::<Vec<PathBuf>>();
+    migrations.sort();
+    migrations
+}
+
+fn get_jvmdata(vendor: &str, version: &str, os: &str) -> JvmData {
+    JvmData {
+        architecture: "x86_64".to_string(),
+        checksum: Some(format!("sha256:{}", "a".repeat(64))),
+        component: "jvm".to_string(),
+        features: Some(vec!["musl".to_string()]),
+        file_type: "tar.gz".to_string(),
+        filename: format!("{}-{}-{}.tar.gz", vendor, version, os),
+        image_type: "jdk".to_string(),
+        java_version: version.to_string(),
+        jvm_impl: "hotspot".to_string(),
+        os: os.to_string(),
+        release_type: "ga".to_string(),
+        size: Some(1024),
+        url: format!("https://example.com/{}-{}-{}.tar.gz", vendor, version, os),
+        vendor: vendor.to_string(),
+        version: version.to_string(),
+        ..Default::default()
+    }
+}
+
+fn get_data() -> HashSet<JvmData> {
+    HashSet::from([
+        get_jvmdata("temurin", "21.0.5+11", "linux"),
+        get_jvmdata("temurin", "17.0.13+11", "linux"),
+        get_jvmdata("temurin", "21.0.5+11", "windows"),
+        get_jvmdata("zulu", "21.0.5+11", "linux"),
+    ])
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_migrations_match_schema() {
+    assert_eq!(Database::start().columns(), Database::start_with_schema().columns());
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_insert() {
+    let db = Database::start();
+    let repo = JvmRepository::new(db.pool.clone()).unwrap();
+
+    assert_eq!(repo.insert(&get_data()).unwrap(), 4);
+    // unchanged records are not updated
+    assert_eq!(repo.insert(&get_data()).unwrap(), 0);
+
+    let mut changed = get_jvmdata("zulu", "21.0.5+11", "linux");
+    changed.size = Some(2048);
+    assert_eq!(repo.insert(&HashSet::from([changed])).unwrap(), 1);
+    assert_eq!(
+        repo.count_by_vendor().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![("temurin".to_string(), 3), ("zulu".to_string(), 1)]
+    );
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_insert_conflicting_urls() {
+    let db = Database::start();
+    let repo = JvmRepository::new(db.pool.clone()).unwrap();
+    let stored = |url: &str| {
+        let rows = db
+            .pool
+            .get()
+            .unwrap()
+            .query("SELECT size, checksum FROM JVM WHERE url = $1", &[&url])
+            .unwrap();
+        rows.iter()
+            .map(|row| {
+                (
+                    row.get::<_, Option<i32>>("size"),
+                    row.get::<_, Option<String>>("checksum"),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // the set keeps the first of two records with the same URL, a batch never repeats the conflict key
+    let first = get_jvmdata("temurin", "21.0.5+11", "linux");
+    let mut duplicate = first.clone();
+    duplicate.size = Some(2048);
+    let batch = HashSet::from([first.clone(), duplicate]);
+    assert_eq!(batch.len(), 1);
+    assert_eq!(repo.insert(&batch).unwrap(), 1);
+    assert_eq!(stored(&first.url), vec![(Some(1024), first.checksum.clone())]);
+
+    // a later batch updates the stored record in place
+    let mut updated = first.clone();
+    updated.size = Some(4096);
+    updated.checksum = Some("sha256:abc".to_string());
+    let other = get_jvmdata("temurin", "17.0.13+11", "linux");
+    assert_eq!(repo.insert(&HashSet::from([updated, other.clone()])).unwrap(), 2);
+    assert_eq!(stored(&first.url), vec![(Some(4096), Some("sha256:abc".to_string()))]);
+    assert_eq!(stored(&other.url), vec![(Some(1024), other.checksum.clone())]);
+    assert_eq!(
+        repo.count_by_vendor().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![("temurin".to_string(), 2)]
+    );
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_export() {
+    let db = Database::start();
+    let repo = JvmRepository::new(db.pool.clone()).unwrap();
+    repo.insert(&get_data()).unwrap();
+
+    let data = repo.export_vendor("temurin", "linux", "x86_64").unwrap();
+    assert_eq!(
+        data.iter().map(|d| d.version.as_str()).collect::<Vec<_>>(),
+        vec!["17.0.13+11", "21.0.5+11"]
+    );
+    // version columns are derived on insert, the other properties on export
+    assert_eq!(data[1].major, Some(21));
+    assert_eq!(data[1].features, Some(vec!["musl".to_string()]));
+    assert_eq!(data[1].bitness, Some(64));
+    assert_eq!(data[1].installer_kind.as_deref(), Some("archive"));
+    assert_eq!(data[1].id, Some(JvmData::id(&data[1].url)));
+    assert_eq!(data[1].age_days, Some(0));
+
+    assert_eq!(
+        repo.export_vendor_major("temurin", 21, "linux", "x86_64")
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(repo.export_release_type("ga", "x86_64", "linux").unwrap().len(), 3);
+    assert_eq!(repo.export_all().unwrap().len(), 4);
+    assert_eq!(repo.get_distinct("vendor").unwrap(), vec!["temurin", "zulu"]);
+    assert_eq!(repo.get_distinct("os").unwrap(), vec!["linux", "windows"]);
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_query() {
+    let db = Database::start();
+    let repo = JvmRepository::new(db.pool.clone()).unwrap();
+    repo.insert(&get_data()).unwrap();
+
+    let filters = HashMap::from([
+        ("vendor".to_string(), vec!["temurin".to_string()]),
+        ("os".to_string(), vec!["linux".to_string()]),
+    ]);
+    let page = Page {
+        sort: vec!["version:desc".parse().unwrap()],
+        limit: Some(1),
+        offset: 0,
+    };
+    let data = repo.query(&filters, &page).unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].version, "21.0.5+11");
+
+    let filters = HashMap::from([("features".to_string(), vec!["lite".to_string()])]);
+    assert!(repo.query(&filters, &Page::default()).unwrap().is_empty());
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_not_found() {
+    let db = Database::start();
+    let repo = NotFoundRepository::new(db.pool.clone()).unwrap();
+    let urls = vec!["https://example.com/a.tar.gz.sha256".to_string()];
+
+    for _ in 0..2 {
+        repo.insert(&urls).unwrap();
+    }
+    assert!(repo.get_permanent(3, 30).unwrap().is_empty());
+    repo.insert(&urls).unwrap();
+    assert_eq!(repo.get_permanent(3, 30).unwrap(), urls.into_iter().collect());
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_asset_checksums() {
+    let db = Database::start();
+    let repo = AssetRepository::new(db.pool.clone()).unwrap();
+    let checksum = |updated_at: &str| AssetChecksum {
+        url: "https://example.com/a.tar.gz".to_string(),
+        updated_at: updated_at.to_string(),
+        checksum: format!("sha256:{}", "b".repeat(64)),
+    };
+
+    assert_eq!(repo.insert(&[checksum("2024-01-16T12:00:00Z")]).unwrap(), 1);
+    assert_eq!(repo.insert(&[checksum("2024-01-16T12:00:00Z")]).unwrap(), 0);
+    assert_eq!(repo.insert(&[checksum("2024-02-01T08:00:00Z")]).unwrap(), 1);
+    assert_eq!(
+        repo.get_all().unwrap().get("https://example.com/a.tar.gz"),
+        Some(&checksum("2024-02-01T08:00:00Z"))
+    );
+}
+
+#[test]
+#[ignore = "requires docker"]
+fn test_fetch_counts() {
+    let db = Database::start();
+    let repo = FetchCountRepository::new(db.pool.clone()).unwrap();
+
+    repo.insert(&BTreeMap::from([
+        ("temurin".to_string(), 100),
+        ("zulu".to_string(), 50),
+    ]))
+    .unwrap();
+    // fetch records 0 for vendors which returned no records or failed, so that they do not keep a stale count
+    repo.insert(&BTreeMap::from([("temurin".to_string(), 0), ("zulu".to_string(), 0)]))
+        .unwrap();
+    assert_eq!(
+        repo.get_all().unwrap(),
+        BTreeMap::from([("temurin".to_string(), 0), ("zulu".to_string(), 0)])
+    );
+}
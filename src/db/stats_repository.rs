@@ -0,0 +1,78 @@
+use eyre::Result;
+use indoc::indoc;
+use postgres_openssl::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+
+use crate::stats::HostStats;
+
+pub struct StatsRepository {
+    pool: Pool<PostgresConnectionManager<MakeTlsConnector>>,
+}
+
+impl StatsRepository {
+    pub fn new(pool: Pool<PostgresConnectionManager<MakeTlsConnector>>) -> Result<Self> {
+        Ok(StatsRepository { pool })
+    }
+
+    pub fn insert(&self, stats: &[HostStats]) -> Result<u64> {
+        let mut conn = self.pool.get()?;
+        let mut tx = conn.transaction()?;
+        let stmt = tx.prepare(
+            "INSERT INTO HTTP_STATS (host, requests, errors, avg_ms, p95_ms, max_ms) VALUES ($1, $2, $3, $4, $5, $6);",
+        )?;
+        let mut result = 0;
+        for s in stats {
+            result += tx.execute(
+                &stmt,
+                &[
+                    &s.host,
+                    &(s.requests as i64),
+                    &(s.errors as i64),
+                    &(s.avg_ms as i64),
+                    &(s.p95_ms as i64),
+                    &(s.max_ms as i64),
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(result)
+    }
+
+    /// Returns the statistics per host of the fetches in the last days
+    ///
+    /// The average is weighted by the number of requests, percentile and maximum are the worst of all fetches
+    pub fn get_by_host(&self, days: i32) -> Result<Vec<HostStats>> {
+        let mut conn = self.pool.get()?;
+        let stmt = indoc! {
+          "SELECT
+              host,
+              SUM(requests)::BIGINT AS requests,
+              SUM(errors)::BIGINT AS errors,
+              (SUM(avg_ms * requests) / NULLIF(SUM(requests), 0))::BIGINT AS avg_ms,
+              MAX(p95_ms) AS p95_ms,
+              MAX(max_ms) AS max_ms
+          FROM
+              HTTP_STATS
+          WHERE
+              created_at::TIMESTAMPTZ >= NOW() - make_interval(days => $1)
+          GROUP BY
+              host
+          ORDER BY
+              host ASC
+          ;",
+        };
+        let rows = conn.query(stmt, &[&days])?;
+        Ok(rows
+            .iter()
+            .map(|row| HostStats {
+                host: row.get("host"),
+                requests: row.get::<_, i64>("requests") as u64,
+                errors: row.get::<_, i64>("errors") as u64,
+                avg_ms: row.get::<_, Option<i64>>("avg_ms").unwrap_or_default() as u64,
+                p95_ms: row.get::<_, i64>("p95_ms") as u64,
+                max_ms: row.get::<_, i64>("max_ms") as u64,
+            })
+            .collect())
+    }
+}
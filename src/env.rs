@@ -5,7 +5,14 @@ use std::{
 
 pub static ARGS: RwLock<Vec<String>> = RwLock::new(vec![]);
 
-pub static ARGV0: LazyLock<String> = LazyLock::new(|| ARGS.read().unwrap()[0].to_string());
+/// The program name, the package name if the arguments are not set e.g. in unit tests
+pub static ARGV0: LazyLock<String> = LazyLock::new(|| {
+    ARGS.read()
+        .unwrap()
+        .first()
+        .cloned()
+        .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string())
+});
 
 pub static BINARY_NAME: LazyLock<&str> = LazyLock::new(|| filename(&ARGV0));
 
@@ -0,0 +1,198 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// Format of the error printed to stderr when a command fails
+#[derive(Clone, Copy, Debug, Default, PartialEq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// Human readable report with the causes of the error
+    #[default]
+    Text,
+    /// A single line of JSON, see [`ErrorReport`]
+    Json,
+}
+
+impl ErrorFormat {
+    /// Returns the format given by `--error-format`, the arguments are not parsed yet if the error occurred in clap
+    pub fn from_args(args: &[String]) -> Self {
+        let value = args
+            .iter()
+            .enumerate()
+            .find_map(|(i, arg)| match arg.strip_prefix("--error-format") {
+                Some("") => args.get(i + 1).map(String::as_str),
+                Some(value) => value.strip_prefix('='),
+                None => None,
+            });
+        match value {
+            Some("json") => ErrorFormat::Json,
+            _ => ErrorFormat::Text,
+        }
+    }
+}
+
+/// Category of an error, stable for automation
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The configuration is missing or invalid
+    Config,
+    /// A query or the connection to the database failed
+    Database,
+    /// A request failed or returned an error status
+    Http,
+    /// A request timed out
+    HttpTimeout,
+    /// GitHub refused a request as the rate limit is exceeded
+    RateLimited,
+    /// A response or file could not be parsed e.g. the format of a vendor changed
+    Parse,
+    /// Reading or writing a file failed
+    Io,
+    /// One or more vendors failed
+    Vendor,
+    /// Any other error
+    Internal,
+}
+
+/// Failure of one or more vendors, carries their names into the error output
+#[derive(Debug)]
+pub struct VendorError {
+    pub vendors: Vec<String>,
+    pub message: String,
+}
+
+impl Display for VendorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for VendorError {}
+
+/// Machine readable error printed by `--error-format json`
+///
+/// ```json
+/// {"code":"http","message":"HTTP status server error (502 Bad Gateway) for url (https://api.github.com/...)","vendor":null,"url":"https://api.github.com/...","hint":"..."}
+/// ```
+/// The code is derived from the first cause of the error with a known type, the message is the outermost one.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ErrorReport {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Vendors which failed, comma separated
+    pub vendor: Option<String>,
+    pub url: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn new(err: &eyre::Report) -> Self {
+        let mut report = ErrorReport {
+            code: ErrorCode::Internal,
+            message: err.to_string(),
+            vendor: None,
+            url: None,
+            hint: None,
+        };
+        for cause in err.chain() {
+            if let Some(err) = cause.downcast_ref::<VendorError>() {
+                report.vendor = Some(err.vendors.join(","));
+                report.classify(ErrorCode::Vendor);
+            } else if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+                report.url = report.url.take().or_else(|| err.url().map(|url| url.to_string()));
+                report.classify(match err.status().map(|status| status.as_u16()) {
+                    Some(403) | Some(429) if report.is_github() => ErrorCode::RateLimited,
+                    _ if err.is_timeout() => ErrorCode::HttpTimeout,
+                    _ => ErrorCode::Http,
+                });
+            } else if cause.is::<confique::Error>() {
+                report.classify(ErrorCode::Config);
+            } else if cause.is::<postgres::Error>() || cause.is::<r2d2::Error>() {
+                report.classify(ErrorCode::Database);
+            } else if cause.is::<serde_json::Error>() {
+                report.classify(ErrorCode::Parse);
+            } else if cause.is::<std::io::Error>() {
+                report.classify(ErrorCode::Io);
+            }
+        }
+        report.hint = report.hint();
+        report
+    }
+
+    /// Sets the code unless a cause closer to the surface already did
+    fn classify(&mut self, code: ErrorCode) {
+        if self.code == ErrorCode::Internal {
+            self.code = code;
+        }
+    }
+
+    fn is_github(&self) -> bool {
+        self.url
+            .as_deref()
+            .is_some_and(|url| url.starts_with("https://api.github.com/"))
+    }
+
+    fn hint(&self) -> Option<String> {
+        let hint = match self.code {
+            ErrorCode::Config => "check config.toml and the ROAST_ environment variables",
+            ErrorCode::Database => "check database.url and whether the database is reachable",
+            ErrorCode::Http | ErrorCode::HttpTimeout => "the request might succeed on retry",
+            ErrorCode::RateLimited => "configure github.token or retry after the rate limit resets",
+            ErrorCode::Parse => "the response format of the vendor might have changed",
+            ErrorCode::Vendor => "run `roast smoke` with the vendors to check them in isolation",
+            ErrorCode::Io | ErrorCode::Internal => return None,
+        };
+        Some(hint.to_string())
+    }
+
+    /// Returns the report as a single line of JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_format_from_args() {
+        for (args, expected) in [
+            (vec!["roast", "fetch"], ErrorFormat::Text),
+            (vec!["roast", "--error-format", "json", "fetch"], ErrorFormat::Json),
+            (vec!["roast", "fetch", "--error-format=json"], ErrorFormat::Json),
+            (vec!["roast", "fetch", "--error-format=text"], ErrorFormat::Text),
+            (vec!["roast", "fetch", "--error-format"], ErrorFormat::Text),
+        ] {
+            let args = args.into_iter().map(String::from).collect::<Vec<_>>();
+            assert_eq!(ErrorFormat::from_args(&args), expected, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_error_report() {
+        let err = eyre::Report::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            .wrap_err("unable to write export/vendor/zulu.json");
+        let report = ErrorReport::new(&err);
+        assert_eq!(report.code, ErrorCode::Io);
+        assert_eq!(report.message, "unable to write export/vendor/zulu.json");
+        assert_eq!(report.hint, None);
+
+        let err = eyre::Report::new(serde_json::from_str::<Vec<String>>("{").unwrap_err());
+        assert_eq!(ErrorReport::new(&err).code, ErrorCode::Parse);
+
+        let err = eyre::Report::new(VendorError {
+            vendors: vec!["temurin".to_string(), "zulu".to_string()],
+            message: "smoke test failed".to_string(),
+        });
+        let report = ErrorReport::new(&err);
+        assert_eq!(report.code, ErrorCode::Vendor);
+        assert_eq!(report.vendor.as_deref(), Some("temurin,zulu"));
+
+        let err = eyre::eyre!("no export views configured");
+        assert_eq!(
+            ErrorReport::new(&err).to_json(),
+            r#"{"code":"internal","message":"no export views configured","vendor":null,"url":null,"hint":null}"#
+        );
+    }
+}
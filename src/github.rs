@@ -1,11 +1,38 @@
-use log::error;
+use std::{
+    collections::BTreeSet,
+    sync::{
+        LazyLock, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use log::{error, info, warn};
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
 use xx::regex;
 
-use crate::http::HTTP;
+use crate::{config::Conf, http::HTTP};
 use eyre::Result;
 
+/// Repositories without releases, collected during `fetch`
+static EMPTY_REPOS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Only the first page of releases is requested, set by `smoke`
+static FIRST_PAGE_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Patterns of the repositories to fetch and to skip configured by `fetch.allow_repos` and `fetch.deny_repos`,
+/// resolved on first use
+static REPO_PATTERNS: LazyLock<(Vec<String>, Vec<String>)> = LazyLock::new(|| match Conf::try_get() {
+    Ok(conf) => (
+        conf.fetch.allow_repos.unwrap_or_default(),
+        conf.fetch.deny_repos.unwrap_or_default(),
+    ),
+    Err(err) => {
+        warn!("unable to load configuration: {}", err);
+        (Vec::new(), Vec::new())
+    }
+});
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRelease {
     pub assets: Vec<GitHubAsset>,
@@ -15,25 +42,27 @@ pub struct GitHubRelease {
     pub tag_name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GitHubTag {
-    pub name: String,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubAsset {
     pub browser_download_url: String,
     pub content_type: String,
     pub name: String,
     pub size: u64,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 pub fn list_releases(repo: &str) -> Result<Vec<GitHubRelease>> {
+    let (allow, deny) = &*REPO_PATTERNS;
+    if !repo_allowed(repo, allow, deny) {
+        info!("[github] skipping {}, excluded by fetch.allow_repos/deny_repos", repo);
+        return Ok(Vec::new());
+    }
     let url = format!("https://api.github.com/repos/{repo}/releases?per_page=100");
 
     let (mut releases, mut headers) = HTTP.get_json_with_headers::<Vec<GitHubRelease>, _>(url)?;
 
-    while let Some(next) = next_page(&headers) {
+    while let Some(next) = next_page(&headers).filter(|_| !FIRST_PAGE_ONLY.load(Ordering::Relaxed)) {
         let (more, h) = match HTTP.get_json_with_headers::<Vec<GitHubRelease>, _>(&next) {
             Ok(result) => result,
             Err(err) => {
@@ -46,10 +75,39 @@ pub fn list_releases(repo: &str) -> Result<Vec<GitHubRelease>> {
         headers = h;
     }
     releases.retain(|r| !r.draft);
+    if releases.is_empty() {
+        // e.g. the repository of a new major version is created before its first release
+        warn!("[github] {} has no releases", repo);
+        EMPTY_REPOS.lock().unwrap().insert(repo.to_string());
+    }
 
     Ok(releases)
 }
 
+/// Requests only the first page of releases of every repository, the most recent 100 releases
+pub fn first_page_only() {
+    FIRST_PAGE_ONLY.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if a repository matches none of the denied patterns and one of the allowed, if any
+fn repo_allowed(repo: &str, allow: &[String], deny: &[String]) -> bool {
+    !deny.iter().any(|pattern| matches_pattern(pattern, repo))
+        && (allow.is_empty() || allow.iter().any(|pattern| matches_pattern(pattern, repo)))
+}
+
+/// Returns true if a repository in form of owner/name matches a pattern with `*` wildcards e.g. `corretto/*-8`
+///
+/// Repositories are matched case-insensitive like GitHub does
+fn matches_pattern(pattern: &str, repo: &str) -> bool {
+    let pattern = pattern.split('*').map(regex::escape).collect::<Vec<_>>().join(".*");
+    regex::Regex::new(&format!("(?i)^{}$", pattern)).is_ok_and(|re| re.is_match(repo))
+}
+
+/// Returns the repositories without releases and resets them
+pub fn take_empty_repos() -> Vec<String> {
+    std::mem::take(&mut *EMPTY_REPOS.lock().unwrap()).into_iter().collect()
+}
+
 fn next_page(headers: &HeaderMap) -> Option<String> {
     let link = headers
         .get("link")
@@ -59,3 +117,32 @@ fn next_page(headers: &HeaderMap) -> Option<String> {
         .captures(&link)
         .map(|c| c.get(1).unwrap().as_str().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_allowed() {
+        let patterns = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        for (repo, allow, deny, expected) in [
+            ("corretto/corretto-8", vec![], vec![], true),
+            ("corretto/corretto-8", vec![], vec!["corretto/corretto-8"], false),
+            ("corretto/corretto-8", vec![], vec!["Corretto/*-8"], false),
+            ("corretto/corretto-11", vec![], vec!["corretto/*-8"], true),
+            ("dragonwell-project/dragonwell8", vec![], vec!["*/dragonwell8"], false),
+            ("SAP/SapMachine", vec!["sap/*"], vec![], true),
+            ("graalvm/mandrel", vec!["sap/*"], vec![], false),
+            ("SAP/SapMachine", vec!["sap/*"], vec!["*"], false),
+        ] {
+            assert_eq!(
+                repo_allowed(repo, &patterns(&allow), &patterns(&deny)),
+                expected,
+                "{} allow={:?} deny={:?}",
+                repo,
+                allow,
+                deny
+            );
+        }
+    }
+}
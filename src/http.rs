@@ -1,18 +1,43 @@
 #![allow(dead_code)]
-use std::sync::LazyLock;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, LazyLock, OnceLock};
+use std::time::{Duration, Instant};
 
 use eyre::Result;
 use log::{debug, warn};
+use openssl::{base64, sha::sha256, x509::X509};
 use reqwest::blocking::{ClientBuilder, RequestBuilder, Response};
 use reqwest::header::HeaderMap;
 use reqwest::{IntoUrl, Url};
+use rustls::client::WebPkiServerVerifier;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
 
+use crate::cassette::{Cassette, CassetteMode, Recording};
+use crate::chaos;
 use crate::cli::version;
+use crate::config::Conf;
 use crate::env;
+use crate::stats;
 
 pub static HTTP: LazyLock<Client> = LazyLock::new(|| Client::new(Duration::from_secs(30)).unwrap());
 
+/// URLs which permanently return 404 and are not requested anymore, only set by `fetch`
+static SKIPPED_URLS: OnceLock<HashSet<String>> = OnceLock::new();
+
+/// GitHub API token, resolved on first use as it might run an external credential command
+static GITHUB_TOKEN: LazyLock<Option<String>> = LazyLock::new(|| match Conf::try_get() {
+    Ok(conf) => conf.github.token().unwrap_or_else(|err| {
+        warn!("unable to resolve GitHub token: {}", err);
+        None
+    }),
+    Err(err) => {
+        warn!("unable to load configuration: {}", err);
+        None
+    }
+});
+
 #[derive(Debug)]
 pub struct Client {
     reqwest: reqwest::blocking::Client,
@@ -20,29 +45,85 @@ pub struct Client {
 
 impl Client {
     fn new(timeout: Duration) -> Result<Self> {
+        // pins are validated when the configuration is loaded, see `crate::config::validate_pins`
+        let pins = match Conf::try_get() {
+            Ok(conf) => conf.http.pins.unwrap_or_default(),
+            Err(err) => {
+                warn!("unable to load configuration: {}", err);
+                HashMap::new()
+            }
+        };
+        let mut builder = Self::_new().timeout(timeout);
+        if !pins.is_empty() {
+            builder = builder.use_preconfigured_tls(pinning_tls_config(pins)?);
+        }
         Ok(Self {
-            reqwest: Self::_new().timeout(timeout).build()?,
+            reqwest: builder.build()?,
         })
     }
 
     fn _new() -> ClientBuilder {
         reqwest::blocking::ClientBuilder::new()
             .user_agent(format!("{}/{}", &*env::BINARY_NAME, &*version::VERSION))
+            // vendor pages are parsed based on their English content
+            .default_headers(HeaderMap::from_iter([(
+                reqwest::header::ACCEPT_LANGUAGE,
+                reqwest::header::HeaderValue::from_static("en-US,en;q=0.9"),
+            )]))
             .gzip(true)
             .zstd(true)
     }
 
     pub fn get<U: IntoUrl>(&self, url: U) -> Result<Response> {
         let url = url.into_url()?;
+        chaos::inject(url.as_str())?;
+        if Cassette::get().is_some_and(|c| c.mode == CassetteMode::Replay) {
+            return Err(eyre::eyre!("streaming responses can not be replayed: {url}"));
+        }
         let mut req = self.reqwest.get(url.clone());
         req = with_github_auth(&url.clone(), req);
-        let resp = req.send()?;
+        let resp = timed_send(&url, req)?;
         debug!("GET {url} {}", resp.status());
         display_github_rate_limit(&resp);
         resp.error_for_status_ref()?;
         Ok(resp)
     }
 
+    /// Sends a HEAD request and returns the status, e.g. to probe whether a download URL is still available
+    ///
+    /// URLs which are known to permanently return 404 are not requested. HEAD requests are recorded and replayed
+    /// like GET requests, the recordings have no body.
+    pub fn head<U: IntoUrl>(&self, url: U) -> Result<u16> {
+        self.head_with(url, Cassette::get())
+    }
+
+    fn head_with<U: IntoUrl>(&self, url: U, cassette: Option<&Cassette>) -> Result<u16> {
+        let url = url.into_url()?;
+        if SKIPPED_URLS.get().is_some_and(|urls| urls.contains(url.as_str())) {
+            debug!("HEAD {url} 404 (skipped as permanent failure)");
+            return Ok(404);
+        }
+        chaos::inject(url.as_str())?;
+        if let Some(cassette) = cassette.filter(|c| c.mode == CassetteMode::Replay) {
+            let recording = cassette.load("HEAD", url.as_str())?;
+            debug!("HEAD {url} {} (replayed)", recording.status);
+            return Ok(recording.status);
+        }
+
+        let resp = timed_send(&url, self.reqwest.head(url.clone()))?;
+        debug!("HEAD {url} {}", resp.status());
+        if let Some(cassette) = cassette.filter(|c| c.mode == CassetteMode::Record) {
+            cassette.save(&Recording {
+                method: "HEAD".to_string(),
+                url: url.to_string(),
+                status: resp.status().as_u16(),
+                headers: headers(&resp),
+                body: String::new(),
+            })?;
+        }
+        Ok(resp.status().as_u16())
+    }
+
     pub fn get_json<T, U: IntoUrl>(&self, url: U) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
@@ -55,33 +136,228 @@ impl Client {
         T: serde::de::DeserializeOwned,
     {
         let url = url.into_url()?;
-        let mut req = self.reqwest.get(url.clone());
-        req = with_github_auth(&url, req);
-        let resp = req.send()?;
-        let headers = resp.headers().clone();
-        debug!("GET {url} {}", resp.status());
-        display_github_rate_limit(&resp);
-        resp.error_for_status_ref()?;
-        Ok::<(T, HeaderMap), eyre::Error>((resp.json()?, headers))
+        let recording = self.send(url, true)?;
+        Ok::<(T, HeaderMap), eyre::Error>((serde_json::from_str(&recording.body)?, recording.header_map()))
     }
 
     pub fn get_text<U: IntoUrl>(&self, url: U) -> Result<String> {
         let url = url.into_url()?;
-        let req = self.reqwest.get(url.clone());
-        let resp = req.send()?;
+        Ok(self.send(url, false)?.body)
+    }
+
+    /// Sends a GET request, or replays it from the cassette in use
+    fn send(&self, url: Url, github_auth: bool) -> Result<Recording> {
+        if SKIPPED_URLS.get().is_some_and(|urls| urls.contains(url.as_str())) {
+            return Err(eyre::eyre!(
+                "HTTP status 404 for url ({}), skipped as permanent failure",
+                url
+            ));
+        }
+        chaos::inject(url.as_str())?;
+        let cassette = Cassette::get();
+        if let Some(cassette) = cassette.filter(|c| c.mode == CassetteMode::Replay) {
+            let recording = cassette.load("GET", url.as_str())?;
+            debug!("GET {url} {} (replayed)", recording.status);
+            if recording.status >= 400 {
+                return Err(eyre::eyre!("HTTP status {} for url ({})", recording.status, url));
+            }
+            return Ok(recording);
+        }
+
+        let mut req = self.reqwest.get(url.clone());
+        if github_auth {
+            req = with_github_auth(&url, req);
+        }
+        let resp = timed_send(&url, req)?;
         debug!("GET {url} {}", resp.status());
-        resp.error_for_status_ref()?;
-        Ok(resp.text()?)
+        if github_auth {
+            display_github_rate_limit(&resp);
+        }
+        let status_err = resp.error_for_status_ref().err();
+        let recording = Recording {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            status: resp.status().as_u16(),
+            headers: headers(&resp),
+            body: resp.text()?,
+        };
+        if let Some(cassette) = cassette.filter(|c| c.mode == CassetteMode::Record) {
+            cassette.save(&recording)?;
+        }
+        match status_err {
+            Some(err) => Err(err.into()),
+            None => Ok(recording),
+        }
     }
 }
 
-fn with_github_auth(url: &Url, mut req: RequestBuilder) -> RequestBuilder {
-    if url.host_str() == Some("api.github.com") {
-        if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-            req = req.header("authorization", format!("token {}", token));
-            req = req.header("x-github-api-version", "2022-11-28");
+/// Returns the headers of a response which are valid strings, cookies are left out of recordings
+fn headers(resp: &Response) -> BTreeMap<String, String> {
+    resp.headers()
+        .iter()
+        .filter(|(name, _)| *name != reqwest::header::SET_COOKIE)
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// Skips requests to URLs which are known to permanently return 404 e.g. checksum URLs of missing checksums
+pub fn skip_urls(urls: HashSet<String>) -> Result<()> {
+    SKIPPED_URLS
+        .set(urls)
+        .map_err(|_| eyre::eyre!("skipped URLs are already set"))
+}
+
+/// Returns a TLS configuration verifying the certificate pins of the hosts during the handshake
+///
+/// Verifying the pins before any request is sent ensures that neither the request nor credentials like the GitHub
+/// token reach an unpinned peer, which also applies to every host a request is redirected to.
+fn pinning_tls_config(pins: HashMap<String, Vec<String>>) -> Result<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    for err in &native.errors {
+        warn!("unable to load native certificate: {}", err);
+    }
+    let (added, _) = roots.add_parsable_certificates(native.certs);
+    if added == 0 {
+        return Err(eyre::eyre!("no native root certificates found to verify pinned hosts"));
+    }
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = PinningVerifier {
+        inner: WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone()).build()?,
+        pins,
+    };
+    Ok(ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth())
+}
+
+/// Verifies the certificate chain and, for a pinned host, that the server certificate matches one of its pins
+#[derive(Debug)]
+struct PinningVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    /// Pinned certificates per host, see [`crate::config::HttpConf::pins`]
+    pins: HashMap<String, Vec<String>>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self
+            .inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+        let host = server_name.to_str();
+        match self.pins.get(host.as_ref()) {
+            Some(pins) => verify_pins(&host, pins, end_entity).map(|_| verified),
+            None => Ok(verified),
         }
     }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Fails unless the DER encoded server certificate matches one of the pins of the host
+fn verify_pins(host: &str, pins: &[String], certificate: &[u8]) -> Result<(), rustls::Error> {
+    let actual = certificate_pins(certificate).map_err(|e| rustls::Error::General(e.to_string()))?;
+    if pins.iter().any(|pin| actual.contains(pin)) {
+        return Ok(());
+    }
+    Err(rustls::Error::General(format!(
+        "certificate pinning failed for {}: server presented {} but expected one of {}",
+        host,
+        actual.join(", "),
+        pins.join(", ")
+    )))
+}
+
+/// Returns the public key and certificate pins of a DER encoded certificate
+fn certificate_pins(der: &[u8]) -> Result<Vec<String>> {
+    let spki = X509::from_der(der)?.public_key()?.public_key_to_der()?;
+    Ok(vec![
+        format!("sha256/{}", base64::encode_block(&sha256(&spki))),
+        format!("cert-sha256/{}", base64::encode_block(&sha256(der))),
+    ])
+}
+
+/// Sends the request and records its latency and whether it failed
+fn timed_send(url: &Url, req: RequestBuilder) -> reqwest::Result<Response> {
+    let start = Instant::now();
+    let resp = req.send();
+    let failed = resp
+        .as_ref()
+        .is_ok_and(|r| r.status().is_client_error() || r.status().is_server_error());
+    stats::record(url, start.elapsed(), resp.is_err() || failed);
+    if let Ok(resp) = &resp {
+        for notice in deprecation_notices(resp.headers()) {
+            if stats::record_deprecation(url, &notice) {
+                warn!(
+                    "DEPRECATED: {} signals a deprecation of its API, migrate before it breaks: {} (e.g. {})",
+                    url.host_str().unwrap_or_default(),
+                    notice,
+                    url
+                );
+            }
+        }
+    }
+    if resp
+        .as_ref()
+        .is_ok_and(|r| r.status() == reqwest::StatusCode::NOT_FOUND)
+    {
+        stats::record_not_found(url);
+    }
+    resp
+}
+
+/// Returns the notices of the `Deprecation`, `Sunset` and `Warning` headers of a response
+///
+/// Vendor APIs signal endpoints which are going away by these headers, see RFC 9745, RFC 8594 and RFC 7234.
+fn deprecation_notices(headers: &HeaderMap) -> Vec<String> {
+    ["deprecation", "sunset", "warning"]
+        .iter()
+        .flat_map(|name| {
+            headers
+                .get_all(*name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(move |value| format!("{}: {}", name, value.trim()))
+        })
+        .collect()
+}
+
+fn with_github_auth(url: &Url, mut req: RequestBuilder) -> RequestBuilder {
+    if url.host_str() == Some("api.github.com")
+        && let Some(token) = GITHUB_TOKEN.as_deref()
+    {
+        req = req.header("authorization", format!("token {}", token));
+        req = req.header("x-github-api-version", "2022-11-28");
+    }
     req
 }
 
@@ -99,3 +375,82 @@ fn display_github_rate_limit(resp: &Response) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        nid::Nid,
+        pkey::PKey,
+        x509::X509Builder,
+    };
+
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_certificate_pins() {
+        let key =
+            PKey::from_ec_key(EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap())
+                .unwrap();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+        builder.sign(&key, openssl::hash::MessageDigest::sha256()).unwrap();
+        let der = builder.build().to_der().unwrap();
+
+        let pins = certificate_pins(&der).unwrap();
+
+        assert_eq!(
+            pins[0],
+            format!(
+                "sha256/{}",
+                base64::encode_block(&sha256(&key.public_key_to_der().unwrap()))
+            )
+        );
+        assert_eq!(pins[1], format!("cert-sha256/{}", base64::encode_block(&sha256(&der))));
+        assert!(certificate_pins(b"invalid").is_err());
+
+        assert!(verify_pins("example.com", &[pins[0].clone()], &der).is_ok());
+        assert!(verify_pins("example.com", &[pins[1].clone()], &der).is_ok());
+        let err = verify_pins("example.com", &["sha256/AAAA".to_string()], &der).unwrap_err();
+        assert!(err.to_string().contains("certificate pinning failed for example.com"));
+    }
+
+    #[test]
+    fn test_deprecation_notices() {
+        let mut headers = HeaderMap::new();
+        assert!(deprecation_notices(&headers).is_empty());
+
+        headers.insert("content-type", "application/json".parse().unwrap());
+        headers.insert("sunset", "Sat, 31 Jan 2026 23:59:59 GMT".parse().unwrap());
+        headers.append("warning", "299 - \"Deprecated API, use /v3/\"".parse().unwrap());
+        headers.append("warning", "299 - \"Parameter os is deprecated\"".parse().unwrap());
+        assert_eq!(
+            deprecation_notices(&headers),
+            vec![
+                "sunset: Sat, 31 Jan 2026 23:59:59 GMT",
+                "warning: 299 - \"Deprecated API, use /v3/\"",
+                "warning: 299 - \"Parameter os is deprecated\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_head_replayed() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes");
+        let cassette = Cassette::new(CassetteMode::Replay, &path);
+        let client = Client::new(Duration::from_secs(1)).unwrap();
+
+        let url = "https://cdn.azul.com/zulu/bin/zulu21.38.21-ca-jdk21.0.5-linux_x64.tar.gz";
+        assert_eq!(client.head_with(url, Some(&cassette)).unwrap(), 200);
+        assert!(
+            client
+                .head_with("https://example.com/unknown.zip", Some(&cassette))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use super::{JvmData, version};
+
+/// Returns true for the major versions with long-term support e.g. 8, 11, 17, 21, 25
+pub fn is_lts(major: i32) -> bool {
+    major == 8 || major == 11 || (major >= 17 && (major - 17) % 4 == 0)
+}
+
+/// Returns the aliases of the versions of a vendor, resolved to the newest GA version they match
+///
+/// Aliases:
+/// ```plaintext
+/// latest        newest version
+/// lts           newest version of a major with long-term support
+/// 21            newest version of a major
+/// 21-lts        newest version of a major with long-term support
+/// 21.0          newest version of a major and minor
+/// ```
+/// Versions are compared by their numeric components, see [`version::compare`]. Aliases resolve to the version as
+/// published by the vendor.
+pub fn aliases(data: &[JvmData]) -> BTreeMap<String, String> {
+    let mut aliases: BTreeMap<String, &JvmData> = BTreeMap::new();
+    for item in data.iter().filter(|item| item.release_type == "ga") {
+        let java_version = version::parse(&item.java_version);
+        let (Some(major), Some(minor)) = (java_version.major, java_version.minor) else {
+            continue;
+        };
+        let mut names = vec!["latest".to_string(), major.to_string(), format!("{}.{}", major, minor)];
+        if is_lts(major) {
+            names.extend(["lts".to_string(), format!("{}-lts", major)]);
+        }
+        for name in names {
+            if aliases
+                .get(&name)
+                .is_none_or(|newest| compare(item, newest) == Ordering::Greater)
+            {
+                aliases.insert(name, item);
+            }
+        }
+    }
+    aliases
+        .into_iter()
+        .map(|(alias, item)| (alias, item.version.clone()))
+        .collect()
+}
+
+fn compare(a: &JvmData, b: &JvmData) -> Ordering {
+    version::compare(&a.java_version, &b.java_version).then_with(|| a.version.cmp(&b.version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_jvmdata(java_version: &str, release_type: &str) -> JvmData {
+        JvmData {
+            java_version: java_version.to_string(),
+            release_type: release_type.to_string(),
+            version: java_version.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_lts() {
+        for (major, expected) in [
+            (8, true),
+            (11, true),
+            (17, true),
+            (21, true),
+            (22, false),
+            (25, true),
+            (9, false),
+        ] {
+            assert_eq!(is_lts(major), expected, "{}", major);
+        }
+    }
+
+    #[test]
+    fn test_aliases() {
+        let data = vec![
+            get_jvmdata("1.8.0_432-b06", "ga"),
+            get_jvmdata("1.8.0_442-b06", "ga"),
+            get_jvmdata("21.0.2+13", "ga"),
+            get_jvmdata("21.0.10+7", "ga"),
+            get_jvmdata("23.0.1+11", "ga"),
+            get_jvmdata("24-ea+3", "ea"),
+        ];
+
+        let aliases = aliases(&data);
+
+        assert_eq!(
+            aliases,
+            BTreeMap::from(
+                [
+                    ("8", "1.8.0_442-b06"),
+                    ("8-lts", "1.8.0_442-b06"),
+                    ("8.0", "1.8.0_442-b06"),
+                    ("21", "21.0.10+7"),
+                    ("21-lts", "21.0.10+7"),
+                    ("21.0", "21.0.10+7"),
+                    ("23", "23.0.1+11"),
+                    ("23.0", "23.0.1+11"),
+                    ("latest", "23.0.1+11"),
+                    ("lts", "21.0.10+7"),
+                ]
+                .map(|(alias, version)| (alias.to_string(), version.to_string()))
+            )
+        );
+    }
+}
@@ -0,0 +1,43 @@
+/// Returns the bitness (32 or 64) of a normalized architecture
+pub fn bitness(architecture: &str) -> Option<i32> {
+    match architecture {
+        "aarch64" | "arm64ec" | "ppc64" | "ppc64le" | "riscv64" | "s390x" | "sparc" | "x86_64" => Some(64),
+        "arm32" | "arm32-vfp-hflt" | "i686" | "ppc32" | "ppc32hf" | "ppc32spe" | "s390" => Some(32),
+        _ => None,
+    }
+}
+
+/// Returns the endianness (little or big) of a normalized architecture
+pub fn endianness(architecture: &str) -> Option<&'static str> {
+    match architecture {
+        "aarch64" | "arm32" | "arm32-vfp-hflt" | "arm64ec" | "i686" | "ppc64le" | "riscv64" | "x86_64" => {
+            Some("little")
+        }
+        "ppc32" | "ppc32hf" | "ppc32spe" | "ppc64" | "s390" | "s390x" | "sparc" => Some("big"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitness_and_endianness() {
+        for (actual, expected) in [
+            ("x86_64", (Some(64), Some("little"))),
+            ("i686", (Some(32), Some("little"))),
+            ("aarch64", (Some(64), Some("little"))),
+            ("arm64ec", (Some(64), Some("little"))),
+            ("arm32-vfp-hflt", (Some(32), Some("little"))),
+            ("ppc64", (Some(64), Some("big"))),
+            ("ppc64le", (Some(64), Some("little"))),
+            ("s390", (Some(32), Some("big"))),
+            ("s390x", (Some(64), Some("big"))),
+            ("sparc", (Some(64), Some("big"))),
+            ("unknown-arch-mips", (None, None)),
+        ] {
+            assert_eq!((bitness(actual), endianness(actual)), expected, "{}", actual);
+        }
+    }
+}
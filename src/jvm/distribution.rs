@@ -0,0 +1,70 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use log::warn;
+
+use super::JvmData;
+use crate::config::{Conf, ExportVendorConf};
+
+/// Export settings of the vendors configured by `export.vendors`, resolved on first use
+static VENDORS: LazyLock<HashMap<String, ExportVendorConf>> = LazyLock::new(|| match Conf::try_get() {
+    Ok(conf) => conf.export.vendors.unwrap_or_default(),
+    Err(err) => {
+        warn!("unable to load configuration: {}", err);
+        HashMap::new()
+    }
+});
+
+/// Sets the distribution terms of an artifact from the settings of its vendor
+///
+/// Restricted artifacts are exported with `restricted: true`, views exclude them by the filter `restricted=false`
+pub fn apply(item: &mut JvmData) {
+    apply_vendor_conf(item, &VENDORS);
+}
+
+fn apply_vendor_conf(item: &mut JvmData, vendors: &HashMap<String, ExportVendorConf>) {
+    if let Some(conf) = vendors.get(&item.vendor) {
+        item.restricted = conf.restricted.unwrap_or_default();
+        item.distribution_notes = conf.distribution_notes.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_vendor_conf() {
+        let vendors = HashMap::from([
+            (
+                "oracle".to_string(),
+                ExportVendorConf {
+                    restricted: Some(true),
+                    distribution_notes: Some("subject to U.S. export regulations".to_string()),
+                },
+            ),
+            (
+                "zulu".to_string(),
+                ExportVendorConf {
+                    distribution_notes: Some("GPLv2 with Classpath Exception".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        for (vendor, restricted, distribution_notes) in [
+            ("oracle", true, Some("subject to U.S. export regulations")),
+            ("zulu", false, Some("GPLv2 with Classpath Exception")),
+            ("temurin", false, None),
+        ] {
+            let mut item = JvmData {
+                vendor: vendor.to_string(),
+                ..Default::default()
+            };
+            apply_vendor_conf(&mut item, &vendors);
+            assert_eq!(item.restricted, restricted, "{}", vendor);
+            assert_eq!(item.distribution_notes.as_deref(), distribution_notes, "{}", vendor);
+
+            let filters = HashMap::from([("restricted".to_string(), vec!["false".to_string()])]);
+            assert_eq!(JvmData::filter(&item, &filters), !restricted, "{}", vendor);
+        }
+    }
+}
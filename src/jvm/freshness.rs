@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+
+/// Returns the number of full days since a record was last verified by `fetch`
+///
+/// `verified_at` is stored as UTC RFC3339. Records verified in the future, e.g. by a clock skew of the database, are
+/// 0 days old. None if the timestamp can not be parsed.
+pub fn age_days(verified_at: &str, now: DateTime<Utc>) -> Option<i32> {
+    let verified_at = DateTime::parse_from_rfc3339(verified_at).ok()?;
+    Some((now - verified_at.with_timezone(&Utc)).num_days().max(0) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_age_days() {
+        let now = DateTime::parse_from_rfc3339("2025-03-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        for (verified_at, expected) in [
+            ("2025-03-15T08:00:00Z", Some(0)),
+            ("2025-03-14T12:00:00Z", Some(1)),
+            ("2025-03-14T12:00:01Z", Some(0)),
+            ("2025-01-14T00:00:00Z", Some(60)),
+            ("2025-03-16T00:00:00Z", Some(0)),
+            ("2025-03-15T14:00:00+02:00", Some(0)),
+            ("invalid", None),
+        ] {
+            assert_eq!(age_days(verified_at, now), expected, "{}", verified_at);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::JvmData;
+
+/// Installer kinds of vendors whose installers differ from the defaults of their file type
+///
+/// Entries are (vendor, file_type, installer_kind), the first matching entry wins
+const VENDOR_HINTS: &[(&str, &str, &str)] = &[
+    // the Oracle exe installers support unattended installs by `/s`
+    ("oracle", "exe", "exe-silent"),
+];
+
+/// Returns how an artifact is installed, derived from its file type and the vendor hints
+///
+/// Kinds:
+/// ```plaintext
+/// archive     extracted e.g. tar.gz, zip
+/// msi         Windows Installer, unattended by `msiexec /i <file> /qn`
+/// exe         Windows installer without a documented unattended mode
+/// exe-silent  Windows installer supporting unattended installs
+/// pkg         macOS installer package, unattended by `installer -pkg <file> -target /`
+/// dmg         macOS disk image which has to be mounted
+/// package     Linux or Solaris package installed by the system package manager e.g. deb, rpm, apk
+/// ```
+pub fn installer_kind(item: &JvmData) -> Option<String> {
+    if let Some((_, _, kind)) = VENDOR_HINTS
+        .iter()
+        .find(|(vendor, file_type, _)| *vendor == item.vendor && *file_type == item.file_type)
+    {
+        return Some(kind.to_string());
+    }
+    let kind = match (item.os.as_str(), item.file_type.as_str()) {
+        (_, "tar.gz" | "tar.xz" | "tar" | "zip" | "7z") => "archive",
+        (_, "msi") => "msi",
+        (_, "exe") => "exe",
+        ("solaris", "pkg") => "package",
+        (_, "pkg") => "pkg",
+        (_, "dmg") => "dmg",
+        (_, "apk" | "deb" | "rpm") => "package",
+        _ => return None,
+    };
+    Some(kind.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installer_kind() {
+        for (vendor, os, file_type, expected) in [
+            ("temurin", "linux", "tar.gz", Some("archive")),
+            ("zulu", "windows", "zip", Some("archive")),
+            ("temurin", "windows", "msi", Some("msi")),
+            ("zulu", "windows", "exe", Some("exe")),
+            ("oracle", "windows", "exe", Some("exe-silent")),
+            ("temurin", "macosx", "pkg", Some("pkg")),
+            ("oracle", "solaris", "pkg", Some("package")),
+            ("liberica", "macosx", "dmg", Some("dmg")),
+            ("corretto", "linux", "rpm", Some("package")),
+            ("liberica", "linux", "apk", Some("package")),
+            ("unknown", "linux", "unknown", None),
+        ] {
+            let jvm_data = JvmData {
+                file_type: file_type.to_string(),
+                os: os.to_string(),
+                vendor: vendor.to_string(),
+                ..Default::default()
+            };
+            assert_eq!(
+                installer_kind(&jvm_data),
+                expected.map(String::from),
+                "{} {} {}",
+                vendor,
+                os,
+                file_type
+            );
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+
+use super::{JvmData, version};
+
+/// Platform constraints of an artifact which installers can validate before downloading it
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PlatformRequirements {
+    /// C library the artifact is linked against, `musl` or `glibc`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub libc: Option<String>,
+    /// Minimum glibc version, see [`GLIBC_REQUIREMENTS`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_glibc: Option<String>,
+}
+
+/// Minimum glibc version required by the Linux builds of a vendor for a range of Java major versions
+struct GlibcRequirement {
+    vendor: &'static str,
+    architecture: Option<&'static str>,
+    min_major: u32,
+    max_major: Option<u32>,
+    glibc: &'static str,
+}
+
+/// Minimum glibc versions as documented by the vendors or inferred from the build platforms they use
+///
+/// Entries are matched in order, the first matching entry wins
+const GLIBC_REQUIREMENTS: &[GlibcRequirement] = &[
+    // https://adoptium.net/supported-platforms/
+    GlibcRequirement {
+        vendor: "temurin",
+        architecture: Some("x86_64"),
+        min_major: 8,
+        max_major: None,
+        glibc: "2.12",
+    },
+    GlibcRequirement {
+        vendor: "temurin",
+        architecture: None,
+        min_major: 8,
+        max_major: None,
+        glibc: "2.17",
+    },
+    // https://docs.aws.amazon.com/corretto/latest/corretto-21-ug/what-is-corretto-21.html
+    GlibcRequirement {
+        vendor: "corretto",
+        architecture: None,
+        min_major: 8,
+        max_major: Some(11),
+        glibc: "2.12",
+    },
+    GlibcRequirement {
+        vendor: "corretto",
+        architecture: None,
+        min_major: 17,
+        max_major: None,
+        glibc: "2.17",
+    },
+    // https://docs.azul.com/core/zulu-openjdk/supported-platforms
+    GlibcRequirement {
+        vendor: "zulu",
+        architecture: None,
+        min_major: 6,
+        max_major: None,
+        glibc: "2.12",
+    },
+    // https://www.oracle.com/java/technologies/javase/products-doc-jdk17certconfig.html
+    GlibcRequirement {
+        vendor: "oracle",
+        architecture: None,
+        min_major: 17,
+        max_major: None,
+        glibc: "2.17",
+    },
+    GlibcRequirement {
+        vendor: "openjdk",
+        architecture: None,
+        min_major: 17,
+        max_major: None,
+        glibc: "2.17",
+    },
+    // https://learn.microsoft.com/en-us/java/openjdk/support
+    GlibcRequirement {
+        vendor: "microsoft",
+        architecture: None,
+        min_major: 11,
+        max_major: None,
+        glibc: "2.17",
+    },
+    GlibcRequirement {
+        vendor: "sapmachine",
+        architecture: None,
+        min_major: 11,
+        max_major: None,
+        glibc: "2.17",
+    },
+];
+
+/// Returns the C library an artifact is linked against, `musl` or `glibc`, or `None` for non Linux artifacts
+pub fn libc(item: &JvmData) -> Option<String> {
+    if item.os != "linux" {
+        return None;
+    }
+    match item.features.as_ref().is_some_and(|f| f.contains(&"musl".to_string())) {
+        true => Some("musl".to_string()),
+        false => Some("glibc".to_string()),
+    }
+}
+
+/// Returns the minimum glibc version required by an artifact if it is known
+pub fn min_glibc_version(item: &JvmData) -> Option<String> {
+    if libc(item).as_deref() != Some("glibc") {
+        return None;
+    }
+    let major = java_major(&item.java_version)?;
+    GLIBC_REQUIREMENTS
+        .iter()
+        .find(|r| {
+            r.vendor == item.vendor
+                && r.architecture.is_none_or(|a| a == item.architecture)
+                && major >= r.min_major
+                && r.max_major.is_none_or(|max| major <= max)
+        })
+        .map(|r| r.glibc.to_string())
+}
+
+/// Returns the platform requirements of an artifact or `None` for non Linux artifacts
+pub fn platform_requirements(item: &JvmData) -> Option<PlatformRequirements> {
+    Some(PlatformRequirements {
+        libc: Some(libc(item)?),
+        min_glibc: min_glibc_version(item),
+    })
+}
+
+/// Returns the major version of a Java version string
+/// Examples:
+/// ```plaintext
+/// 1.8.0_392 -> 8
+/// 21.0.2+13 -> 21
+/// 8u392+9 -> 8
+/// ```
+pub fn java_major(java_version: &str) -> Option<u32> {
+    version::parse(java_version).major.map(|major| major as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_jvmdata(vendor: &str, architecture: &str, java_version: &str) -> JvmData {
+        JvmData {
+            architecture: architecture.to_string(),
+            java_version: java_version.to_string(),
+            os: "linux".to_string(),
+            vendor: vendor.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_java_major() {
+        for (actual, expected) in [
+            ("1.8.0_392", Some(8)),
+            ("8u392+9", Some(8)),
+            ("11.0.2", Some(11)),
+            ("21.0.2+13-LTS", Some(21)),
+            ("invalid", None),
+        ] {
+            assert_eq!(java_major(actual), expected);
+        }
+    }
+
+    #[test]
+    fn test_libc() {
+        let mut jvm_data = get_jvmdata("temurin", "x86_64", "21.0.2");
+        assert_eq!(libc(&jvm_data), Some("glibc".to_string()));
+        jvm_data.features = Some(vec!["musl".to_string()]);
+        assert_eq!(libc(&jvm_data), Some("musl".to_string()));
+        jvm_data.os = "windows".to_string();
+        assert_eq!(libc(&jvm_data), None);
+    }
+
+    #[test]
+    fn test_min_glibc_version() {
+        for (vendor, architecture, java_version, expected) in [
+            ("temurin", "x86_64", "21.0.2", Some("2.12")),
+            ("temurin", "aarch64", "21.0.2", Some("2.17")),
+            ("corretto", "x86_64", "11.0.22", Some("2.12")),
+            ("corretto", "x86_64", "17.0.10", Some("2.17")),
+            ("oracle", "x86_64", "11.0.22", None),
+            ("unknown", "x86_64", "21.0.2", None),
+        ] {
+            let jvm_data = get_jvmdata(vendor, architecture, java_version);
+            assert_eq!(min_glibc_version(&jvm_data), expected.map(|s| s.to_string()));
+        }
+
+        let mut jvm_data = get_jvmdata("temurin", "x86_64", "21.0.2");
+        jvm_data.features = Some(vec!["musl".to_string()]);
+        assert_eq!(min_glibc_version(&jvm_data), None);
+    }
+
+    #[test]
+    fn test_platform_requirements() {
+        let mut jvm_data = get_jvmdata("temurin", "aarch64", "21.0.2");
+        assert_eq!(
+            serde_json::to_value(platform_requirements(&jvm_data)).unwrap(),
+            serde_json::json!({ "libc": "glibc", "min_glibc": "2.17" })
+        );
+        jvm_data.features = Some(vec!["musl".to_string()]);
+        assert_eq!(
+            serde_json::to_value(platform_requirements(&jvm_data)).unwrap(),
+            serde_json::json!({ "libc": "musl" })
+        );
+        jvm_data.os = "macosx".to_string();
+        assert_eq!(platform_requirements(&jvm_data), None);
+    }
+}
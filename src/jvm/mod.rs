@@ -3,22 +3,63 @@ use serde_json::{Map, Value, json};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 
+#[cfg(feature = "cli")]
+pub mod alias;
+#[cfg(feature = "cli")]
+pub mod arch;
+#[cfg(feature = "cli")]
+pub mod distribution;
+#[cfg(feature = "cli")]
+pub mod freshness;
+#[cfg(feature = "cli")]
+pub mod installer;
+pub mod libc;
+#[cfg(feature = "cli")]
 pub mod vendor;
+pub mod version;
+
+/// Properties which change without the data, e.g. daily, and are only exported with `--include`
+pub const OPT_IN: &[&str] = &["age_days"];
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
+// exported files might not contain all properties, see `export --include/--exclude`
+#[serde(default)]
 pub struct JvmData {
+    /// Days since `fetch` last found the record at its vendor, derived on export from verified_at
+    pub age_days: Option<i32>,
     pub architecture: String,
+    /// Bitness of the architecture, derived on export
+    pub bitness: Option<i32>,
+    pub build: Option<i32>,
     pub checksum: Option<String>,
     pub checksum_url: Option<String>,
+    /// Component of the artifact e.g. jvm, javafx, jmc
+    pub component: String,
+    /// Notes on the distribution terms of the vendor, derived on export from `export.vendors`
+    pub distribution_notes: Option<String>,
+    /// Endianness of the architecture, derived on export
+    pub endianness: Option<String>,
     #[serde(serialize_with = "empty_vec_if_none")]
     pub features: Option<Vec<String>>,
     pub file_type: String,
     pub filename: String,
+    /// Stable identifier of the artifact, derived on export, see [`JvmData::id`]
+    pub id: Option<String>,
     pub image_type: String,
+    /// How the artifact is installed e.g. archive, msi, pkg, derived on export
+    pub installer_kind: Option<String>,
     pub java_version: String,
     pub jvm_impl: String,
+    pub major: Option<i32>,
+    pub minor: Option<i32>,
+    pub openjdk_version: Option<String>,
     pub os: String,
+    pub patch: Option<i32>,
+    /// Platform constraints e.g. `{ "libc": "musl" }`, derived from the vendor rules if not set by the vendor
+    pub platform_requirements: Option<libc::PlatformRequirements>,
     pub release_type: String,
+    /// Whether the distribution is restricted e.g. by export-control, derived on export from `export.vendors`
+    pub restricted: bool,
     pub size: Option<i32>,
     pub url: String,
     pub vendor: String,
@@ -52,6 +93,17 @@ impl PartialEq for JvmData {
 impl Eq for JvmData {}
 
 impl JvmData {
+    /// Returns the first 16 hex digits of the SHA-256 of the URL, the identity of an artifact
+    ///
+    /// The id does not change when mutable properties like the checksum or size are updated
+    #[cfg(feature = "cli")]
+    pub fn id(url: &str) -> String {
+        openssl::sha::sha256(url.as_bytes())[..8]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
     pub fn filter(item: &JvmData, filters: &HashMap<String, Vec<String>>) -> bool {
         if filters.is_empty() {
             return true;
@@ -64,11 +116,16 @@ impl JvmData {
         true
     }
 
+    /// Returns the exported properties of a record, properties of [`OPT_IN`] only if they are included explicitly
     pub fn map(item: &JvmData, include: &[String], exclude: &[String]) -> Map<String, Value> {
         let props: HashMap<String, Value> = serde_json::from_value(serde_json::to_value(item).unwrap()).unwrap();
         let mut map = Map::new();
         for prop in &props {
-            if (include.is_empty() || include.contains(prop.0)) && !exclude.contains(prop.0) {
+            let included = match include.is_empty() {
+                true => !OPT_IN.contains(&prop.0.as_str()),
+                false => include.contains(prop.0),
+            };
+            if included && !exclude.contains(prop.0) {
                 map.insert(prop.0.clone(), json!(prop.1.clone()));
             }
         }
@@ -129,6 +186,7 @@ mod tests {
             url: "http://example.com/download".to_string(),
             vendor: "AdoptOpenJDK".to_string(),
             version: "11.0.2".to_string(),
+            ..Default::default()
         }
     }
 
@@ -194,6 +252,7 @@ mod tests {
         let jvm_data = get_jvmdata();
 
         let include = vec![
+            "age_days".to_string(),
             "architecture".to_string(),
             "checksum".to_string(),
             "checksum_url".to_string(),
@@ -213,6 +272,7 @@ mod tests {
 
         let map = JvmData::map(&jvm_data, &include, &[]);
 
+        assert!(map.contains_key("age_days"));
         assert_eq!(map.get("architecture").unwrap(), "x86_64");
         assert_eq!(map.get("checksum").unwrap(), "sha256:checksum");
         assert_eq!(map.get("checksum_url").unwrap(), "http://example.com/checksum");
@@ -285,8 +345,17 @@ mod tests {
         assert!(map.get("os").is_none());
         assert_eq!(map.get("release_type").unwrap(), "ga");
         assert!(map.get("size").is_none());
+        assert!(map.get("age_days").is_none());
         assert_eq!(map.get("url").unwrap(), "http://example.com/download");
         assert_eq!(map.get("vendor").unwrap(), "AdoptOpenJDK");
         assert_eq!(map.get("version").unwrap(), "11.0.2");
     }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_id() {
+        let jvm_data = get_jvmdata();
+        assert_eq!(JvmData::id(&jvm_data.url), "7b544315e4aef271");
+        assert_ne!(JvmData::id(&jvm_data.url), JvmData::id("http://example.com/download2"));
+    }
 }
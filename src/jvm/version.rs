@@ -0,0 +1,120 @@
+use std::cmp::Ordering;
+
+use xx::regex;
+
+/// Components of a Java version e.g. `11.0.25+11` or `1.8.0_432-b06`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JavaVersion {
+    pub major: Option<i32>,
+    pub minor: Option<i32>,
+    pub patch: Option<i32>,
+    pub build: Option<i32>,
+    /// Version in form of `{major}.{minor}.{patch}[+{build}]`, legacy versions use the update as patch
+    pub openjdk_version: Option<String>,
+}
+
+/// Parses the components of a Java version
+///
+/// Legacy versions (`1.8.0_432`) are mapped to `8.0.432`. Vendor specific components after the patch are ignored,
+/// the build is taken from a `+11` or `-b06` suffix.
+/// Examples:
+/// ```plaintext
+/// 11.0.25+11 -> 11, 0, 25, 11
+/// 1.8.0_432-b06 -> 8, 0, 432, 6
+/// 21.0.2.0.101 -> 21, 0, 2
+/// ```
+pub fn parse(version: &str) -> JavaVersion {
+    let (major, minor, patch) = if let Some(caps) = regex!(r"^1\.([1-9])(?:\.0(?:[._]([0-9]+))?)?").captures(version) {
+        (
+            caps.get(1).and_then(|m| m.as_str().parse().ok()),
+            Some(0),
+            Some(caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0)),
+        )
+    } else if let Some(caps) = regex!(r"^([0-9]+)(?:\.([0-9]+))?(?:\.([0-9]+))?").captures(version) {
+        (
+            caps.get(1).and_then(|m| m.as_str().parse().ok()),
+            Some(caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0)),
+            Some(caps.get(3).and_then(|m| m.as_str().parse().ok()).unwrap_or(0)),
+        )
+    } else {
+        return JavaVersion::default();
+    };
+    let build = regex!(r"(?:\+|-b)([0-9]+)")
+        .captures(version)
+        .and_then(|caps| caps.get(1)?.as_str().parse().ok());
+    let openjdk_version = match (major, minor, patch, build) {
+        (Some(major), Some(minor), Some(patch), Some(build)) => Some(format!("{major}.{minor}.{patch}+{build}")),
+        (Some(major), Some(minor), Some(patch), None) => Some(format!("{major}.{minor}.{patch}")),
+        _ => None,
+    };
+    JavaVersion {
+        major,
+        minor,
+        patch,
+        build,
+        openjdk_version,
+    }
+}
+
+/// Compares Java versions by their numeric components, versions which can not be parsed sort first
+///
+/// Versions of equal components e.g. `21.0.2+13` and `21.0.2+13-LTS` are compared as text
+pub fn compare(a: &str, b: &str) -> Ordering {
+    let (va, vb) = (parse(a), parse(b));
+    (va.major, va.minor, va.patch, va.build)
+        .cmp(&(vb.major, vb.minor, vb.patch, vb.build))
+        .then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        for (actual, expected) in [
+            (
+                "11.0.25+11",
+                (Some(11), Some(0), Some(25), Some(11), Some("11.0.25+11")),
+            ),
+            (
+                "1.8.0_432-b06",
+                (Some(8), Some(0), Some(432), Some(6), Some("8.0.432+6")),
+            ),
+            ("1.8.0", (Some(8), Some(0), Some(0), None, Some("8.0.0"))),
+            ("21", (Some(21), Some(0), Some(0), None, Some("21.0.0"))),
+            ("21.0.2.0.101", (Some(21), Some(0), Some(2), None, Some("21.0.2"))),
+            ("17.0.7.7.1", (Some(17), Some(0), Some(7), None, Some("17.0.7"))),
+            ("24-ea+3", (Some(24), Some(0), Some(0), Some(3), Some("24.0.0+3"))),
+            ("jdk", (None, None, None, None, None)),
+        ] {
+            let version = parse(actual);
+            assert_eq!(
+                (
+                    version.major,
+                    version.minor,
+                    version.patch,
+                    version.build,
+                    version.openjdk_version.as_deref()
+                ),
+                expected,
+                "failed to parse {}",
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        for (a, b, expected) in [
+            ("21.0.2+13", "21.0.10+7", Ordering::Less),
+            ("1.8.0_432-b06", "11.0.25+11", Ordering::Less),
+            ("17.0.13+11", "17.0.13+9", Ordering::Greater),
+            ("21.0.2", "21.0.2+13", Ordering::Less),
+            ("21.0.2+13", "21.0.2+13", Ordering::Equal),
+            ("jdk", "8", Ordering::Less),
+        ] {
+            assert_eq!(compare(a, b), expected, "{} <=> {}", a, b);
+        }
+    }
+}
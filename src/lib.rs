@@ -0,0 +1,42 @@
+//! A JVM Data Crawler
+//!
+//! The crate is mainly the `roast` binary, built with the default `cli` feature. With the `client` feature it also
+//! provides [`client::Catalog`] which reads the exported files, without the `cli` feature the library only contains
+//! the client and the data model of the exported files.
+#[cfg(feature = "cli")]
+pub mod build_time;
+#[cfg(feature = "cli")]
+mod build_tool;
+#[cfg(feature = "cli")]
+mod cassette;
+#[cfg(feature = "cli")]
+mod chaos;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "cli")]
+mod config;
+#[cfg(feature = "cli")]
+mod db;
+#[cfg(feature = "cli")]
+mod env;
+#[cfg(feature = "cli")]
+pub mod error;
+#[cfg(feature = "cli")]
+mod github;
+#[cfg(feature = "cli")]
+mod http;
+// the client only uses the data model of the exported files
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+mod jvm;
+#[cfg(feature = "cli")]
+mod mem;
+#[cfg(feature = "cli")]
+pub mod output;
+#[cfg(feature = "cli")]
+mod report;
+#[cfg(feature = "cli")]
+mod script;
+#[cfg(feature = "cli")]
+mod stats;
@@ -1,17 +1,18 @@
-use cli::Cli;
 use color_eyre::{Section, SectionExt};
 use itertools::Itertools;
+use roast::{
+    cli::{Cli, version::VERSION},
+    error::{ErrorFormat, ErrorReport},
+    output,
+};
 
-use crate::cli::version::VERSION;
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
-pub mod build_time;
-mod cli;
-mod config;
-mod db;
-mod env;
-mod github;
-mod http;
-mod jvm;
+#[cfg(all(feature = "mimalloc", not(feature = "jemalloc")))]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 fn main() -> eyre::Result<()> {
     env_logger::builder()
@@ -22,15 +23,17 @@ fn main() -> eyre::Result<()> {
     let args = std::env::args().collect_vec();
     match Cli::run(&args).with_section(|| VERSION.to_string().header("Version:")) {
         Ok(()) => Ok(()),
-        Err(err) => handle_err(err),
+        Err(err) => handle_err(err, ErrorFormat::from_args(&args)),
     }
 }
 
-fn handle_err(err: eyre::Report) -> eyre::Result<()> {
-    if let Some(err) = err.downcast_ref::<std::io::Error>() {
-        if err.kind() == std::io::ErrorKind::BrokenPipe {
-            return Ok(());
-        }
+fn handle_err(err: eyre::Report, format: ErrorFormat) -> eyre::Result<()> {
+    if output::is_broken_pipe(&err) {
+        return Ok(());
+    }
+    if format == ErrorFormat::Json {
+        eprintln!("{}", ErrorReport::new(&err).to_json());
+        std::process::exit(1);
     }
     Err(err)
 }
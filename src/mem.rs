@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::info;
+
+/// Whether memory statistics are logged, only set by `fetch --mem-stats`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Memory usage of the process
+#[derive(Debug, Default, PartialEq)]
+pub struct MemStats {
+    /// Resident set size in KiB
+    pub rss_kb: Option<u64>,
+    /// Peak resident set size in KiB
+    pub peak_rss_kb: Option<u64>,
+    /// Bytes allocated by the application, only known with the `jemalloc` allocator
+    pub allocated: Option<u64>,
+}
+
+/// Enables logging memory statistics at the end of every stage
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Logs the memory statistics at the end of a stage if enabled
+///
+/// Statistics are process wide, stages running in parallel e.g. vendors are included in each other's numbers.
+pub fn stage(name: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let stats = snapshot();
+    let mib = |v: Option<u64>| v.map_or("-".to_string(), |v| format!("{:.1} MiB", v as f64 / 1024.0));
+    info!(
+        "[mem] {}: rss {}, peak rss {}, allocated {}",
+        name,
+        mib(stats.rss_kb),
+        mib(stats.peak_rss_kb),
+        mib(stats.allocated.map(|a| a / 1024))
+    );
+}
+
+/// Returns the current memory statistics, the RSS is only known on Linux
+pub fn snapshot() -> MemStats {
+    let (rss_kb, peak_rss_kb) = match std::fs::read_to_string("/proc/self/status") {
+        Ok(status) => parse_status(&status),
+        Err(_) => (None, None),
+    };
+    MemStats {
+        rss_kb,
+        peak_rss_kb,
+        allocated: allocated(),
+    }
+}
+
+#[cfg(feature = "jemalloc")]
+fn allocated() -> Option<u64> {
+    // jemalloc caches its statistics until the epoch is advanced
+    tikv_jemalloc_ctl::epoch::advance().ok()?;
+    tikv_jemalloc_ctl::stats::allocated::read().ok().map(|a| a as u64)
+}
+
+#[cfg(not(feature = "jemalloc"))]
+fn allocated() -> Option<u64> {
+    None
+}
+
+/// Returns the RSS and peak RSS in KiB of a /proc/{pid}/status file
+fn parse_status(status: &str) -> (Option<u64>, Option<u64>) {
+    let value = |key: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+    };
+    (value("VmRSS:"), value("VmHWM:"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let status = "Name:\troast\nVmPeak:\t 1048576 kB\nVmHWM:\t  524288 kB\nVmRSS:\t  262144 kB\nThreads:\t8\n";
+        assert_eq!(parse_status(status), (Some(262144), Some(524288)));
+        assert_eq!(parse_status("Name:\troast\n"), (None, None));
+    }
+}
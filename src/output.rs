@@ -0,0 +1,230 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::Result;
+use serde::Serialize;
+
+/// Writes text to stdout
+///
+/// Unlike `print!` a closed pipe e.g. `roast plan | head -1` is returned as error, which ends the command cleanly.
+pub fn print(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(text.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Writes a line to stdout, see [`print`]
+pub fn println(text: &str) -> Result<()> {
+    print(&format!("{}\n", text))
+}
+
+/// Writes a value as JSON line to stdout, see [`print`]
+pub fn print_json<T: Serialize + ?Sized>(value: &T, pretty: bool) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    write_json(&mut stdout, value, pretty)?;
+    writeln!(stdout)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Writes a value as JSON, I/O errors are returned as `std::io::Error` to detect closed pipes
+pub fn write_json<W: Write, T: Serialize + ?Sized>(writer: W, value: &T, pretty: bool) -> Result<()> {
+    let result = match pretty {
+        true => serde_json::to_writer_pretty(writer, value),
+        false => serde_json::to_writer(writer, value),
+    };
+    match result {
+        Err(err) if err.is_io() => Err(std::io::Error::from(err).into()),
+        Err(err) => Err(err.into()),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Writes a value as JSON file
+///
+/// With a Brotli quality a pre-compressed copy is written to {path}.br, the quality is capped at 11. Without, a
+/// copy of a previous export is removed, otherwise a CDN preferring pre-compressed files would serve stale data.
+pub fn write_json_file<T: Serialize + ?Sized>(path: &Path, value: &T, pretty: bool, brotli: Option<u32>) -> Result<()> {
+    let mut json = Vec::new();
+    write_json(&mut json, value, pretty)?;
+    std::fs::write(path, &json)?;
+    match brotli {
+        Some(quality) => {
+            let mut writer = brotli::CompressorWriter::new(File::create(brotli_path(path))?, 4096, quality.min(11), 22);
+            writer.write_all(&json)?;
+            writer.into_inner().sync_all()?;
+        }
+        None => match std::fs::remove_file(brotli_path(path)) {
+            Err(err) if err.kind() != ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+        },
+    }
+    Ok(())
+}
+
+/// Encodings of the exported files, written to {dir}/manifest.json in form of {"vendor/os/arch.json": ["identity", "br"]}
+///
+/// `identity` is the plain file and `br` its pre-compressed {path}.br copy. Entries of a previous manifest are kept, so
+/// exporting a single vendor doesn't drop the other files of the directory.
+pub struct Manifest {
+    dir: PathBuf,
+    files: BTreeMap<String, Vec<String>>,
+}
+
+impl Manifest {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let files = match std::fs::read(dir.join("manifest.json")) {
+            Ok(json) => serde_json::from_slice(&json)
+                .map_err(|e| eyre::eyre!("unable to parse {}: {}", dir.join("manifest.json").display(), e))?,
+            Err(err) if err.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Manifest {
+            dir: dir.to_path_buf(),
+            files,
+        })
+    }
+
+    /// Writes a JSON file by [`write_json_file`] and records its encodings
+    pub fn write_json_file<T: Serialize + ?Sized>(
+        &mut self,
+        path: &Path,
+        value: &T,
+        pretty: bool,
+        brotli: Option<u32>,
+    ) -> Result<()> {
+        let name = path
+            .strip_prefix(&self.dir)
+            .map_err(|_| eyre::eyre!("{} is not in {}", path.display(), self.dir.display()))?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        write_json_file(path, value, pretty, brotli)?;
+        let encodings = match brotli {
+            Some(_) => vec!["identity".to_string(), "br".to_string()],
+            None => vec!["identity".to_string()],
+        };
+        self.files.insert(name, encodings);
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        write_json_file(&self.dir.join("manifest.json"), &self.files, true, None)
+    }
+}
+
+fn brotli_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".br");
+    PathBuf::from(path)
+}
+
+/// Returns true if any cause of the error is a closed pipe
+pub fn is_broken_pipe(err: &eyre::Report) -> bool {
+    err.chain().any(|cause| match cause.downcast_ref::<std::io::Error>() {
+        Some(err) => err.kind() == ErrorKind::BrokenPipe,
+        None => cause
+            .downcast_ref::<serde_json::Error>()
+            .is_some_and(|err| err.io_error_kind() == Some(ErrorKind::BrokenPipe)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    struct ClosedPipe;
+
+    impl Write for ClosedPipe {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::from(ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_write_json_broken_pipe() {
+        for pretty in [true, false] {
+            let err = write_json(ClosedPipe, &vec!["a", "b"], pretty).unwrap_err();
+            assert!(is_broken_pipe(&err));
+        }
+    }
+
+    #[test]
+    fn test_write_json_file_brotli() {
+        let dir = std::env::temp_dir().join(format!("roast-output-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("x86_64.json");
+
+        write_json_file(&path, &vec!["a", "b"], false, Some(11)).unwrap();
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        let mut decompressed = String::new();
+        brotli::Decompressor::new(File::open(dir.join("x86_64.json.br")).unwrap(), 4096)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(json, r#"["a","b"]"#);
+        assert_eq!(decompressed, json);
+
+        write_json_file(&path, &vec!["a"], false, None).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), r#"["a"]"#);
+        assert!(!dir.join("x86_64.json.br").exists());
+        write_json_file(&path, &vec!["a"], false, None).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest() {
+        let dir = std::env::temp_dir().join(format!("roast-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("zulu/linux")).unwrap();
+
+        let mut manifest = Manifest::load(&dir).unwrap();
+        manifest
+            .write_json_file(&dir.join("zulu/linux/x86_64.json"), &vec!["a"], false, Some(5))
+            .unwrap();
+        manifest.save().unwrap();
+
+        let mut manifest = Manifest::load(&dir).unwrap();
+        manifest
+            .write_json_file(&dir.join("zulu/linux/aarch64.json"), &vec!["a"], false, None)
+            .unwrap();
+        manifest.save().unwrap();
+
+        let json: serde_json::Value = serde_json::from_reader(File::open(dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "zulu/linux/aarch64.json": ["identity"],
+                "zulu/linux/x86_64.json": ["identity", "br"],
+            })
+        );
+        assert!(
+            Manifest::load(&dir)
+                .unwrap()
+                .write_json_file(&std::env::temp_dir().join("x86_64.json"), &vec!["a"], false, None)
+                .is_err()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_broken_pipe() {
+        let err = eyre::Report::from(std::io::Error::from(ErrorKind::BrokenPipe)).wrap_err("failed to export");
+        assert!(is_broken_pipe(&err));
+        let err = eyre::Report::from(std::io::Error::from(ErrorKind::NotFound));
+        assert!(!is_broken_pipe(&err));
+        assert!(!is_broken_pipe(&eyre::eyre!("no candidates found")));
+    }
+}
@@ -0,0 +1,258 @@
+use std::{fmt::Display, sync::Mutex};
+
+use eyre::Result;
+use lettre::{Message, SmtpTransport, Transport, transport::smtp::authentication::Credentials};
+use log::info;
+
+use crate::config::Conf;
+
+/// Summary of a fetch or export run
+///
+/// The report is emailed to the configured address once the run is finished
+#[derive(Debug)]
+pub struct Report {
+    command: String,
+    entries: Mutex<Vec<ReportEntry>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct ReportEntry {
+    name: String,
+    records: u64,
+    error: Option<String>,
+    /// Reason why a source returned no data e.g. a repository without releases
+    empty: Option<String>,
+    /// Deprecation notice of a vendor API e.g. a `Sunset` header
+    deprecated: Option<String>,
+}
+
+impl Report {
+    pub fn new(command: &str) -> Self {
+        Report {
+            command: command.to_string(),
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Records a successful step e.g. a vendor fetch or an exported file
+    pub fn success(&self, name: &str, records: u64) {
+        self.entries.lock().unwrap().push(ReportEntry {
+            name: name.to_string(),
+            records,
+            error: None,
+            empty: None,
+            deprecated: None,
+        });
+    }
+
+    /// Records a step which succeeded without any data, e.g. a vendor repository which has no releases (yet)
+    pub fn empty<E: Display>(&self, name: &str, reason: E) {
+        self.entries.lock().unwrap().push(ReportEntry {
+            name: name.to_string(),
+            records: 0,
+            error: None,
+            empty: Some(reason.to_string()),
+            deprecated: None,
+        });
+    }
+
+    /// Records a deprecation notice of a vendor API, the step itself is recorded separately
+    pub fn deprecated<E: Display>(&self, name: &str, notice: E) {
+        self.entries.lock().unwrap().push(ReportEntry {
+            name: name.to_string(),
+            records: 0,
+            error: None,
+            empty: None,
+            deprecated: Some(notice.to_string()),
+        });
+    }
+
+    /// Records a failed step
+    pub fn failure<E: Display>(&self, name: &str, err: E) {
+        self.entries.lock().unwrap().push(ReportEntry {
+            name: name.to_string(),
+            records: 0,
+            error: Some(err.to_string()),
+            empty: None,
+            deprecated: None,
+        });
+    }
+
+    fn entries(&self) -> Vec<ReportEntry> {
+        let mut entries = self.entries.lock().unwrap().clone();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
+    /// Returns the number of failed steps
+    pub fn failures(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.error.is_some())
+            .count()
+    }
+
+    /// Returns the number of steps without data
+    pub fn empties(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.empty.is_some())
+            .count()
+    }
+
+    /// Returns the number of deprecation notices
+    pub fn deprecations(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.deprecated.is_some())
+            .count()
+    }
+
+    /// Returns the number of records of all successful steps
+    #[cfg(test)]
+    pub fn records(&self) -> u64 {
+        self.entries.lock().unwrap().iter().map(|e| e.records).sum()
+    }
+
+    pub fn subject(&self) -> String {
+        match (self.failures(), self.empties()) {
+            (0, 0) if self.deprecations() > 0 => format!(
+                "[roast] {} succeeded with {} deprecation notice(s)",
+                self.command,
+                self.deprecations()
+            ),
+            (0, 0) => format!("[roast] {} succeeded", self.command),
+            (0, empties) => format!("[roast] {} succeeded with {} empty source(s)", self.command, empties),
+            (failures, _) => format!("[roast] {} finished with {} failure(s)", self.command, failures),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let entries = self.entries();
+        let (failed, succeeded): (Vec<_>, Vec<_>) = entries.iter().partition(|e| e.error.is_some());
+        let (deprecated, succeeded): (Vec<_>, Vec<_>) = succeeded.into_iter().partition(|e| e.deprecated.is_some());
+        let (empty, succeeded): (Vec<_>, Vec<_>) = succeeded.into_iter().partition(|e| e.empty.is_some());
+        let mut body = format!("{}\n", self.subject());
+        if !failed.is_empty() {
+            body.push_str("\nFAILED\n");
+            for entry in &failed {
+                body.push_str(&format!(
+                    "  [{}] {}\n",
+                    entry.name,
+                    entry.error.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        if !deprecated.is_empty() {
+            body.push_str("\nDEPRECATED\n");
+            for entry in &deprecated {
+                body.push_str(&format!(
+                    "  [{}] {}\n",
+                    entry.name,
+                    entry.deprecated.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        if !empty.is_empty() {
+            body.push_str("\nEMPTY\n");
+            for entry in &empty {
+                body.push_str(&format!(
+                    "  [{}] {}\n",
+                    entry.name,
+                    entry.empty.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+        if !succeeded.is_empty() {
+            body.push_str("\nSUCCEEDED\n");
+            for entry in &succeeded {
+                body.push_str(&format!("  [{}] {} records\n", entry.name, entry.records));
+            }
+        }
+        body
+    }
+
+    /// Emails the report if `report.email_to` is configured
+    pub fn send(&self) -> Result<()> {
+        let conf = Conf::try_get()?.report;
+        let Some(email_to) = conf.email_to.as_deref() else {
+            return Ok(());
+        };
+        let smtp_host = conf
+            .smtp_host
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("report.smtp_host is not configured"))?;
+
+        let email_from = conf.email_from.as_deref().unwrap_or("roast@localhost");
+        let mut builder = Message::builder().from(email_from.parse()?).subject(self.subject());
+        for to in email_to.split(',') {
+            builder = builder.to(to.trim().parse()?);
+        }
+        let message = builder.body(self.body())?;
+
+        let mut transport = SmtpTransport::starttls_relay(smtp_host)?.port(conf.smtp_port.unwrap_or(587));
+        if let (Some(username), Some(password)) = (conf.smtp_username.clone(), conf.smtp_password()?) {
+            transport = transport.credentials(Credentials::new(username, password));
+        }
+        transport.build().send(&message)?;
+
+        info!("sent {} report to {}", self.command, email_to);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body() {
+        let report = Report::new("fetch");
+        report.success("zulu", 42);
+        report.failure("temurin", "connection reset");
+        report.success("corretto", 7);
+        report.empty("github:corretto/corretto-26", "repository has no releases");
+        report.deprecated("http:api.azul.com", "sunset: Sat, 31 Jan 2026 23:59:59 GMT");
+
+        assert_eq!(report.subject(), "[roast] fetch finished with 1 failure(s)");
+        assert_eq!(
+            report.body(),
+            indoc::indoc! {"
+            [roast] fetch finished with 1 failure(s)
+
+            FAILED
+              [temurin] connection reset
+
+            DEPRECATED
+              [http:api.azul.com] sunset: Sat, 31 Jan 2026 23:59:59 GMT
+
+            EMPTY
+              [github:corretto/corretto-26] repository has no releases
+
+            SUCCEEDED
+              [corretto] 7 records
+              [zulu] 42 records
+            "}
+        );
+    }
+
+    #[test]
+    fn test_subject_without_failures() {
+        let report = Report::new("export");
+        report.success("ga/linux/x86_64.json", 1);
+        assert_eq!(report.subject(), "[roast] export succeeded");
+        report.deprecated("http:api.adoptium.net", "deprecation: true");
+        assert_eq!(
+            report.subject(),
+            "[roast] export succeeded with 1 deprecation notice(s)"
+        );
+        report.empty("javafx", "no records fetched");
+        assert_eq!(report.subject(), "[roast] export succeeded with 1 empty source(s)");
+    }
+}
@@ -0,0 +1,154 @@
+use eyre::Result;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde_json::{Map, Value};
+
+use crate::config::Conf;
+
+/// Maximum number of operations a script may run per row before it is aborted
+#[cfg(feature = "scripting")]
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// A user provided script transforming exported rows
+///
+/// The script must define a function `transform(row)` which receives a row as a map and returns the modified row or
+/// `()` to drop it e.g.:
+///
+/// ```rhai
+/// fn transform(row) {
+///     let url = row.url;
+///     url.replace("https://github.com/", "https://proxy.example.com/");
+///     row.url = url;
+///     row
+/// }
+/// ```
+///
+/// Scripts run in a sandboxed [rhai](https://rhai.rs) engine without file system or network access and are only
+/// available if roast is built with the `scripting` feature.
+pub struct RowScript {
+    #[cfg(feature = "scripting")]
+    engine: rhai::Engine,
+    #[cfg(feature = "scripting")]
+    ast: rhai::AST,
+}
+
+impl RowScript {
+    /// Loads the script configured by `export.script`, returns `None` if no script is configured
+    pub fn load(conf: &Conf) -> Result<Option<Self>> {
+        match &conf.export.script {
+            Some(path) => Self::from_file(&shellexpand::tilde(path)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(feature = "scripting")]
+    fn from_file(path: &str) -> Result<Self> {
+        Self::compile(&std::fs::read_to_string(path)?)
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    fn from_file(_path: &str) -> Result<Self> {
+        Err(eyre::eyre!(
+            "export.script is configured but roast was built without the `scripting` feature"
+        ))
+    }
+
+    #[cfg(feature = "scripting")]
+    pub(crate) fn compile(script: &str) -> Result<Self> {
+        let mut engine = rhai::Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_string_size(64 * 1024);
+        engine.disable_symbol("eval");
+        let ast = engine
+            .compile(script)
+            .map_err(|e| eyre::eyre!("failed to compile export script: {}", e))?;
+        Ok(RowScript { engine, ast })
+    }
+
+    /// Transforms a row, returns `None` if the row should be dropped
+    #[cfg(feature = "scripting")]
+    pub fn transform(&self, row: Map<String, Value>) -> Result<Option<Map<String, Value>>> {
+        let input = rhai::serde::to_dynamic(&row).map_err(|e| eyre::eyre!("{}", e))?;
+        let output: rhai::Dynamic = self
+            .engine
+            .call_fn(&mut rhai::Scope::new(), &self.ast, "transform", (input,))
+            .map_err(|e| eyre::eyre!("export script failed: {}", e))?;
+        if output.is_unit() {
+            return Ok(None);
+        }
+        rhai::serde::from_dynamic(&output).map_err(|e| eyre::eyre!("export script returned an invalid row: {}", e))
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    pub fn transform(&self, row: Map<String, Value>) -> Result<Option<Map<String, Value>>> {
+        Ok(Some(row))
+    }
+}
+
+/// Transforms all rows with the script if one is configured
+pub fn transform_rows(script: &Option<RowScript>, rows: Vec<Map<String, Value>>) -> Result<Vec<Map<String, Value>>> {
+    match script {
+        Some(_) => rows
+            .into_par_iter()
+            .filter_map(|row| transform_row(script, row).transpose())
+            .collect(),
+        None => Ok(rows),
+    }
+}
+
+/// Transforms a row with the script if one is configured, returns `None` if the script dropped the row
+pub fn transform_row(script: &Option<RowScript>, row: Map<String, Value>) -> Result<Option<Map<String, Value>>> {
+    match script {
+        Some(script) => script.transform(row),
+        None => Ok(Some(row)),
+    }
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn get_row(url: &str) -> Map<String, Value> {
+        let mut row = Map::new();
+        row.insert("url".to_string(), json!(url));
+        row.insert("features".to_string(), json!(["musl"]));
+        row
+    }
+
+    #[test]
+    fn test_transform_rows() {
+        let script = RowScript::compile(indoc::indoc! {r#"
+            fn transform(row) {
+                if row.url.ends_with(".zip") {
+                    return ();
+                }
+                let url = row.url;
+                url.replace("https://github.com/", "https://proxy.example.com/");
+                row.url = url;
+                row
+            }
+        "#})
+        .unwrap();
+
+        let rows = transform_rows(
+            &Some(script),
+            vec![
+                get_row("https://github.com/a.tar.gz"),
+                get_row("https://github.com/b.zip"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get("url").unwrap(), "https://proxy.example.com/a.tar.gz");
+        assert_eq!(rows[0].get("features").unwrap(), &json!(["musl"]));
+    }
+
+    #[test]
+    fn test_transform_aborts_endless_loop() {
+        let script = RowScript::compile("fn transform(row) { loop {} }").unwrap();
+        assert!(script.transform(get_row("https://github.com/a.tar.gz")).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, btree_map::Entry},
+    sync::Mutex,
+    time::Duration,
+};
+
+use reqwest::Url;
+
+/// Latencies and errors of the HTTP requests per host, collected during `fetch`
+static REQUESTS: Mutex<BTreeMap<String, HostRequests>> = Mutex::new(BTreeMap::new());
+
+/// URLs which returned 404, collected during `fetch`
+static NOT_FOUND: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Deprecation notices of the vendor APIs by host and notice with the first URL returning them, collected during `fetch`
+static DEPRECATIONS: Mutex<BTreeMap<(String, String), String>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Default)]
+struct HostRequests {
+    latencies: Vec<u64>,
+    errors: u64,
+}
+
+/// Latency and error statistics of a host
+#[derive(Clone, Debug, PartialEq)]
+pub struct HostStats {
+    pub host: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub avg_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Records the latency of a request and whether it failed
+pub fn record(url: &Url, latency: Duration, failed: bool) {
+    let host = url.host_str().unwrap_or_default().to_string();
+    let mut requests = REQUESTS.lock().unwrap();
+    let entry = requests.entry(host).or_default();
+    entry.latencies.push(latency.as_millis() as u64);
+    if failed {
+        entry.errors += 1;
+    }
+}
+
+/// Records a URL which returned 404
+pub fn record_not_found(url: &Url) {
+    NOT_FOUND.lock().unwrap().insert(url.to_string());
+}
+
+/// Returns the URLs which returned 404 and resets them
+pub fn take_not_found() -> Vec<String> {
+    std::mem::take(&mut *NOT_FOUND.lock().unwrap()).into_iter().collect()
+}
+
+/// Records a deprecation notice of a host, returns true if the host did not return the notice before
+pub fn record_deprecation(url: &Url, notice: &str) -> bool {
+    let host = url.host_str().unwrap_or_default().to_string();
+    let mut deprecations = DEPRECATIONS.lock().unwrap();
+    match deprecations.entry((host, notice.to_string())) {
+        Entry::Occupied(_) => false,
+        Entry::Vacant(entry) => {
+            entry.insert(url.to_string());
+            true
+        }
+    }
+}
+
+/// Returns the deprecation notices as host, notice and first URL and resets them
+pub fn take_deprecations() -> Vec<(String, String, String)> {
+    std::mem::take(&mut *DEPRECATIONS.lock().unwrap())
+        .into_iter()
+        .map(|((host, notice), url)| (host, notice, url))
+        .collect()
+}
+
+/// Returns the statistics of all hosts and resets the collected requests
+pub fn take() -> Vec<HostStats> {
+    std::mem::take(&mut *REQUESTS.lock().unwrap())
+        .into_iter()
+        .map(|(host, requests)| host_stats(host, requests))
+        .collect()
+}
+
+fn host_stats(host: String, mut requests: HostRequests) -> HostStats {
+    requests.latencies.sort_unstable();
+    let count = requests.latencies.len() as u64;
+    let percentile = |p: u64| match count {
+        0 => 0,
+        n => requests.latencies[((n * p).div_ceil(100) as usize).saturating_sub(1)],
+    };
+    HostStats {
+        host,
+        requests: count,
+        errors: requests.errors,
+        avg_ms: requests
+            .latencies
+            .iter()
+            .sum::<u64>()
+            .checked_div(count)
+            .unwrap_or_default(),
+        p95_ms: percentile(95),
+        max_ms: requests.latencies.last().copied().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_stats() {
+        let requests = HostRequests {
+            latencies: (1..=100).rev().collect(),
+            errors: 3,
+        };
+        assert_eq!(
+            host_stats("api.adoptium.net".to_string(), requests),
+            HostStats {
+                host: "api.adoptium.net".to_string(),
+                requests: 100,
+                errors: 3,
+                avg_ms: 50,
+                p95_ms: 95,
+                max_ms: 100,
+            }
+        );
+        assert_eq!(
+            host_stats("example.com".to_string(), HostRequests::default()),
+            HostStats {
+                host: "example.com".to_string(),
+                requests: 0,
+                errors: 0,
+                avg_ms: 0,
+                p95_ms: 0,
+                max_ms: 0,
+            }
+        );
+    }
+}
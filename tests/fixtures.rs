@@ -0,0 +1,46 @@
+//! Replays the fixtures in `tests/cassettes`, in a test binary of its own as the cassette is used by the whole process
+
+use std::path::PathBuf;
+
+use roast::cli::Cli;
+use serde_json::Value;
+
+#[test]
+fn test_check_fixtures() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/cassettes");
+    let output = std::env::temp_dir().join(format!("roast-fixtures-{}.json", std::process::id()));
+    let args = [
+        "roast",
+        "dev",
+        "check-fixtures",
+        "--dir",
+        dir.to_str().unwrap(),
+        "--output",
+        output.to_str().unwrap(),
+    ];
+
+    Cli::run(&args.iter().map(|arg| arg.to_string()).collect()).unwrap();
+
+    let records: Vec<Value> = serde_json::from_reader(std::fs::File::open(&output).unwrap()).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    let field = |record: &Value, name: &str| record[name].as_str().unwrap_or_default().to_string();
+    let zulu = records
+        .iter()
+        .filter(|r| field(r, "vendor") == "zulu")
+        .collect::<Vec<_>>();
+    assert_eq!(zulu.len(), 2);
+    assert_eq!(
+        field(zulu[0], "filename"),
+        "zulu21.38.21-ca-fx-jdk21.0.5-macosx_aarch64.zip"
+    );
+    assert_eq!(zulu[0]["features"], serde_json::json!(["javafx"]));
+    assert_eq!(field(zulu[1], "filename"), "zulu21.38.21-ca-jdk21.0.5-linux_x64.tar.gz");
+    assert_eq!(field(zulu[1], "java_version"), "21.0.5");
+    for graalvm in records.iter().filter(|r| field(r, "vendor") == "oracle-graalvm") {
+        let url = field(graalvm, "url");
+        assert!(!url.contains("/latest/"), "unresolved URL {}", url);
+        if field(graalvm, "filename").starts_with("graalvm-jdk-21") {
+            assert_eq!(field(graalvm, "java_version"), "21.0.5");
+        }
+    }
+}